@@ -52,7 +52,7 @@ EXAMPLES:
 
 
 SUPPORTED FORMATS:
-    JSON, YAML, CSV, Plain Text (automatically detected)
+    JSON, YAML, TOML, CSV, Plain Text (automatically detected)
 
 QUERY SYNTAX:
     # Field Access
@@ -104,7 +104,6 @@ OUTPUT FORMATS:
 COLORED OUTPUT:
     Automatic color detection (TTY), respects NO_COLOR environment variable
 ")]
-
 pub struct Args {
     /// JSONPath-style query to execute
     ///
@@ -131,25 +130,148 @@ pub struct Args {
     ///
     ///    json: Force JSON output
     ///
+    ///    jsonl: One compact JSON value per line (JSON Lines)
+    ///
     ///    list: Force list output
     ///
     ///    csv: Force CSV output
+    ///
+    ///    toml: TOML output (arrays are written under an "items" key; null
+    ///    fields and array elements are dropped with a warning on stderr)
+    ///
+    ///    yaml: YAML output (see --yaml-documents for one document per result)
+    ///
+    ///    parquet: Parquet file written to stdout (requires the `parquet` feature)
     #[arg(long, default_value = "auto")]
-    #[arg(value_parser = ["auto", "table", "json", "list", "csv"])]
+    #[arg(value_parser = ["auto", "table", "json", "jsonl", "list", "csv", "toml", "yaml", "parquet"])]
     pub format: String,
 
+    /// Write each result as its own YAML document, separated by "---"
+    ///
+    /// Requires --format yaml. Results read from multi-document YAML (those
+    /// with a document_index) are always written this way.
+    #[arg(long)]
+    pub yaml_documents: bool,
+
+    #[arg(long, short, conflicts_with = "indent")]
+    #[arg(help = "Print JSON on a single line (same as --indent 0)")]
+    pub compact: bool,
+
+    #[arg(long)]
+    #[arg(help = "Number of spaces used to indent JSON output (default: 2)")]
+    pub indent: Option<usize>,
+
+    /// Print string results without quotes or colors, one result per line
+    ///
+    /// Other values are printed as JSON. Works with --format json and jsonl.
+    #[arg(long, short)]
+    pub raw_output: bool,
+
     #[arg(long, short)]
     #[arg(help = "Force text format (skip auto-detection)")]
     pub text: bool,
+
+    #[arg(long, conflicts_with = "text")]
+    #[arg(help = "Parse each line as logfmt key=value pairs")]
+    pub logfmt: bool,
+
+    /// Input format (skip auto-detection)
+    ///
+    /// Without this flag the file extension decides the format (.json, .jsonl,
+    /// .yaml, .toml, .xml, .csv, .tsv, .txt, also inside .gz etc.); content is
+    /// only sniffed for stdin and unknown extensions.
+    ///
+    ///    json, jsonl (ndjson), yaml (yml), toml, xml, csv, tsv, text
+    ///
+    ///    logfmt: key=value pairs per line
+    ///
+    ///    clf: Common Log Format
+    ///
+    ///    apache: Apache combined log (falls back to CLF)
+    ///
+    ///    nginx: nginx combined access log
+    ///
+    ///    syslog: RFC 3164 syslog
+    #[arg(long, conflicts_with_all = ["text", "logfmt"])]
+    #[arg(value_parser = [
+        "json", "jsonl", "ndjson", "yaml", "yml", "toml", "xml", "csv", "tsv", "text",
+        "logfmt", "clf", "apache", "nginx", "syslog",
+    ])]
+    pub input_format: Option<String>,
+
+    /// CSV field delimiter (auto-detected if omitted)
+    ///
+    /// A single character such as ',', ';' or '|', or '\t' / "tab" for TSV
+    #[arg(long, conflicts_with_all = ["text", "logfmt"])]
+    pub delimiter: Option<String>,
+
+    #[arg(long, conflicts_with_all = ["text", "logfmt"])]
+    #[arg(help = "CSV quote character (default: '\"')")]
+    pub quote: Option<String>,
+
+    #[arg(long, conflicts_with_all = ["text", "logfmt"])]
+    #[arg(help = "Treat the first CSV row as data (columns are named col1..colN)")]
+    pub no_header: bool,
+
+    #[arg(long, default_value_t = 0, conflicts_with_all = ["text", "logfmt"])]
+    #[arg(help = "Skip the first N lines before reading CSV")]
+    pub skip_rows: usize,
+
+    #[arg(long, conflicts_with_all = ["text", "logfmt"])]
+    #[arg(help = "Keep all CSV values as strings (no type inference)")]
+    pub no_infer: bool,
+
+    /// JSON file with CSV column types
+    ///
+    /// Example: {"zip": "string", "price": "float"}
+    ///
+    /// Types: string, int, float, bool
+    #[arg(long, conflicts_with_all = ["text", "logfmt"])]
+    pub schema: Option<PathBuf>,
+
+    /// CSV column types (overrides --schema)
+    ///
+    /// Example: --col-type zip=string,price=float
+    #[arg(long, conflicts_with_all = ["text", "logfmt"])]
+    pub col_type: Option<String>,
+
+    /// Spreadsheet sheet to read, by name, or by 0-based index when no sheet has that name (default: first sheet)
+    #[arg(long)]
+    pub sheet: Option<String>,
+
+    /// XML elements that are always read as arrays, even when they appear once
+    ///
+    /// Example: --xml-array item,testcase
+    #[arg(long, conflicts_with_all = ["text", "logfmt"])]
+    pub xml_array: Option<String>,
 }
 
+/// JSON 出力のデフォルトのインデント幅
+pub const DEFAULT_JSON_INDENT: usize = 2;
+
 #[derive(Debug, Clone)]
 pub enum OutputFormat {
     Auto,
-    Json,
+    /// indent が None なら1行で出力、raw なら文字列を引用符なしで出力
+    Json {
+        indent: Option<usize>,
+        raw: bool,
+    },
+    JsonLines {
+        raw: bool,
+    },
     Table,
     List,
     Csv,
+    Toml,
+    /// documents が true なら結果ごとに別のドキュメントとして出力。
+    /// indexed_documents は入力が複数ドキュメントの YAML で、document_index を追加したかどうか
+    Yaml {
+        documents: bool,
+        indexed_documents: bool,
+    },
+    #[cfg(feature = "parquet")]
+    Parquet,
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -158,12 +280,27 @@ impl std::str::FromStr for OutputFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "auto" => Ok(OutputFormat::Auto),
-            "json" => Ok(OutputFormat::Json),
+            "json" => Ok(OutputFormat::Json {
+                indent: Some(DEFAULT_JSON_INDENT),
+                raw: false,
+            }),
+            "jsonl" | "ndjson" => Ok(OutputFormat::JsonLines { raw: false }),
             "table" => Ok(OutputFormat::Table),
             "list" => Ok(OutputFormat::List),
             "csv" => Ok(OutputFormat::Csv),
+            "toml" => Ok(OutputFormat::Toml),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml {
+                documents: false,
+                indexed_documents: false,
+            }),
+            #[cfg(feature = "parquet")]
+            "parquet" => Ok(OutputFormat::Parquet),
+            #[cfg(not(feature = "parquet"))]
+            "parquet" => Err(Error::InvalidFormat(
+                "parquet output requires hawk to be built with the `parquet` feature".to_string(),
+            )),
             _ => Err(Error::InvalidFormat(format!(
-                "Invalid format: {}. Valid options: auto, json, table, list",
+                "Invalid format: {}. Valid options: auto, json, jsonl, table, list, csv, toml, yaml, parquet",
                 s
             ))),
         }
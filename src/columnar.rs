@@ -0,0 +1,199 @@
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::{FileReader, StreamReader};
+use arrow_json::reader::infer_json_schema_from_iterator;
+use arrow_json::writer::JsonArray;
+use arrow_json::{ReaderBuilder, WriterBuilder};
+use arrow_schema::{ArrowError, DataType, FieldRef, Fields, Schema};
+use bytes::Bytes;
+use indexmap::IndexSet;
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use serde_json::{Map, Value};
+
+use crate::{ARROW_FILE_MAGIC, Error};
+
+/// Parquet を書き出す際の1バッチあたりの行数
+const WRITE_BATCH_SIZE: usize = 1024;
+
+/// オブジェクト以外の値を書き出すときの列名
+const VALUE_COLUMN: &str = "value";
+
+/// Parquet ファイルをレコードの配列として読み込む（ネストした列はネストした JSON になる）
+pub fn read_parquet(bytes: &[u8]) -> Result<Value, Error> {
+    let reader =
+        ParquetRecordBatchReaderBuilder::try_new(Bytes::copy_from_slice(bytes))?.build()?;
+    let batches = reader.collect::<Result<Vec<RecordBatch>, ArrowError>>()?;
+
+    batches_to_json(&batches)
+}
+
+/// Arrow IPC（ファイル形式・ストリーム形式）をレコードの配列として読み込む
+pub fn read_arrow_ipc(bytes: &[u8]) -> Result<Value, Error> {
+    let cursor = Cursor::new(bytes);
+
+    let batches = if bytes.starts_with(ARROW_FILE_MAGIC) {
+        FileReader::try_new(cursor, None)?.collect::<Result<Vec<RecordBatch>, ArrowError>>()?
+    } else {
+        StreamReader::try_new(cursor, None)?.collect::<Result<Vec<RecordBatch>, ArrowError>>()?
+    };
+
+    batches_to_json(&batches)
+}
+
+fn batches_to_json(batches: &[RecordBatch]) -> Result<Value, Error> {
+    // null の列も省略せずに出力する
+    let mut writer = WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, JsonArray>(Vec::new());
+    writer.write_batches(&batches.iter().collect::<Vec<_>>())?;
+    writer.finish()?;
+
+    let json = writer.into_inner();
+    if json.is_empty() {
+        return Ok(Value::Array(Vec::new()));
+    }
+
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// 結果を Parquet として書き出す（スキーマは値から推論）
+pub fn write_parquet<W: Write + Send>(data: &[Value], writer: W) -> Result<(), Error> {
+    // オブジェクト以外の値は value 列に入れる
+    let rows: Vec<Value> = data
+        .iter()
+        .map(|value| match value {
+            Value::Object(_) => value.clone(),
+            _ => Value::Object(Map::from_iter([(VALUE_COLUMN.to_string(), value.clone())])),
+        })
+        .collect();
+
+    let inferred = infer_json_schema_from_iterator(rows.iter().map(Ok))?;
+    let row_refs: Vec<&Value> = rows.iter().collect();
+    let schema = Arc::new(Schema::new(ordered_fields(inferred.fields(), &row_refs)));
+
+    let mut decoder = ReaderBuilder::new(schema.clone())
+        .with_batch_size(WRITE_BATCH_SIZE)
+        .with_coerce_primitive(true)
+        .build_decoder()?;
+    let mut writer = ArrowWriter::try_new(writer, schema, None)?;
+
+    for chunk in rows.chunks(WRITE_BATCH_SIZE) {
+        decoder.serialize(chunk)?;
+        if let Some(batch) = decoder.flush()? {
+            writer.write(&batch)?;
+        }
+    }
+    writer.close()?;
+
+    Ok(())
+}
+
+/// 推論されたスキーマの列を、元のデータでキーが現れた順に並べ替える
+fn ordered_fields(fields: &Fields, values: &[&Value]) -> Fields {
+    let mut names = IndexSet::new();
+    for value in values {
+        if let Value::Object(map) = value {
+            names.extend(map.keys().map(String::as_str));
+        }
+    }
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (_, field) = fields.find(name)?;
+            let children: Vec<&Value> = values.iter().filter_map(|value| value.get(name)).collect();
+            Some(ordered_field(field, children))
+        })
+        .collect()
+}
+
+fn ordered_field(field: &FieldRef, values: Vec<&Value>) -> FieldRef {
+    let data_type = match field.data_type() {
+        DataType::Struct(children) => DataType::Struct(ordered_fields(children, &values)),
+        DataType::List(item) => {
+            let items = values
+                .iter()
+                .filter_map(|value| value.as_array())
+                .flatten()
+                .collect();
+            DataType::List(ordered_field(item, items))
+        }
+        _ => return field.clone(),
+    };
+
+    Arc::new(field.as_ref().clone().with_data_type(data_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PARQUET_MAGIC;
+    use serde_json::json;
+
+    fn orders_fixture() -> &'static [u8] {
+        include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/examples/small/orders.parquet"
+        ))
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let data = vec![
+            json!({"id": 1, "name": "alice", "tags": ["a", "b"], "address": {"city": "Tokyo", "zip": "100-0001"}}),
+            json!({"id": 2, "name": null, "tags": [], "address": {"city": "Osaka", "zip": null}}),
+        ];
+
+        let mut buffer = Vec::new();
+        write_parquet(&data, &mut buffer).unwrap();
+        assert!(buffer.starts_with(PARQUET_MAGIC));
+
+        let result = read_parquet(&buffer).unwrap();
+        assert_eq!(result, Value::Array(data));
+
+        // 列の順序も保たれる
+        let keys: Vec<&String> = result[0].as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["id", "name", "tags", "address"]);
+        let keys: Vec<&String> = result[0]["address"].as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["city", "zip"]);
+    }
+
+    #[test]
+    fn test_parquet_scalar_values() {
+        let mut buffer = Vec::new();
+        write_parquet(&[json!(1.5), json!(2)], &mut buffer).unwrap();
+
+        assert_eq!(
+            read_parquet(&buffer).unwrap(),
+            json!([{"value": 1.5}, {"value": 2.0}])
+        );
+    }
+
+    #[test]
+    fn test_read_parquet_fixture() {
+        let orders = read_parquet(orders_fixture()).unwrap();
+        let orders = orders.as_array().unwrap();
+
+        assert_eq!(orders.len(), 25);
+        assert_eq!(orders[0]["order_id"], json!("ORD001"));
+        assert_eq!(orders[0]["quantity"], json!(2));
+        assert_eq!(orders[0]["price"], json!(299.99));
+    }
+
+    #[test]
+    fn test_read_arrow_ipc_fixture() {
+        let fixture = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/examples/small/orders.arrow"
+        ));
+        assert!(fixture.starts_with(ARROW_FILE_MAGIC));
+
+        assert_eq!(
+            read_arrow_ipc(fixture).unwrap(),
+            read_parquet(orders_fixture()).unwrap()
+        );
+    }
+}
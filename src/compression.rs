@@ -0,0 +1,178 @@
+use std::io::{Cursor, Read};
+
+use crate::Error;
+
+/// 入力の圧縮形式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl Compression {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Bzip2 => "bzip2",
+            Compression::Xz => "xz",
+        }
+    }
+
+    fn magic(&self) -> &'static [u8] {
+        match self {
+            Compression::Gzip => &[0x1F, 0x8B],
+            Compression::Zstd => &[0x28, 0xB5, 0x2F, 0xFD],
+            Compression::Bzip2 => b"BZh",
+            Compression::Xz => &[0xFD, b'7', b'z', b'X', b'Z', 0x00],
+        }
+    }
+
+    /// 先頭がこの形式のヘッダーか（bzip2 は "BZh" の後のブロックサイズ 1-9 も確認）
+    fn matches(&self, header: &[u8]) -> bool {
+        let magic = self.magic();
+        if !header.starts_with(magic) {
+            return false;
+        }
+
+        match self {
+            Compression::Bzip2 => matches!(header.get(magic.len()), Some(b'1'..=b'9')),
+            _ => true,
+        }
+    }
+}
+
+/// 先頭のマジックナンバーから圧縮形式を判定
+pub fn detect_compression(header: &[u8]) -> Option<Compression> {
+    [
+        Compression::Gzip,
+        Compression::Zstd,
+        Compression::Bzip2,
+        Compression::Xz,
+    ]
+    .into_iter()
+    .find(|compression| compression.matches(header))
+}
+
+/// 入力を読み込み、圧縮されていれば展開する（拡張子ではなく内容で判定）
+///
+/// 展開に失敗しても入力全体がテキストとして読める場合は、マジックナンバーと
+/// たまたま同じ文字で始まるテキスト（"BZh1 ..." など）とみなしてそのまま返す。
+pub fn read_decompressed<R: Read>(mut reader: R) -> Result<Vec<u8>, Error> {
+    let mut raw = Vec::new();
+    reader.read_to_end(&mut raw)?;
+
+    let Some(compression) = detect_compression(&raw) else {
+        return Ok(raw);
+    };
+
+    match decompress(&raw, compression) {
+        Ok(buffer) => Ok(buffer),
+        Err(_) if std::str::from_utf8(&raw).is_ok() => {
+            eprintln!(
+                "Warning: input starts like {} data but could not be decompressed; reading it as plain text",
+                compression.name()
+            );
+            Ok(raw)
+        }
+        Err(e) => Err(decompression_error(e, compression.name())),
+    }
+}
+
+fn decompress(raw: &[u8], compression: Compression) -> std::io::Result<Vec<u8>> {
+    let input = Cursor::new(raw);
+    let mut decoder: Box<dyn Read> = match compression {
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(input)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(input)?),
+        Compression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(input)),
+        Compression::Xz => Box::new(liblzma::read::XzDecoder::new_multi_decoder(input)),
+    };
+
+    let mut buffer = Vec::new();
+    decoder.read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn decompression_error(error: std::io::Error, format: &str) -> Error {
+    Error::Decompression(format!("invalid {} data: {}", format, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const CONTENT: &[u8] = b"2024-01-15 ERROR db timeout\n2024-01-15 INFO ok\n";
+
+    #[test]
+    fn test_decompress_formats() {
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(CONTENT).unwrap();
+        let gzip = gzip.finish().unwrap();
+
+        let zstd = zstd::encode_all(CONTENT, 0).unwrap();
+
+        let mut bzip2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        bzip2.write_all(CONTENT).unwrap();
+        let bzip2 = bzip2.finish().unwrap();
+
+        let mut xz = liblzma::write::XzEncoder::new(Vec::new(), 6);
+        xz.write_all(CONTENT).unwrap();
+        let xz = xz.finish().unwrap();
+
+        for (compressed, expected) in [
+            (gzip, Compression::Gzip),
+            (zstd, Compression::Zstd),
+            (bzip2, Compression::Bzip2),
+            (xz, Compression::Xz),
+        ] {
+            assert_eq!(detect_compression(&compressed), Some(expected));
+            assert_eq!(read_decompressed(compressed.as_slice()).unwrap(), CONTENT);
+        }
+    }
+
+    #[test]
+    fn test_plain_and_short_input() {
+        assert_eq!(detect_compression(CONTENT), None);
+        assert_eq!(read_decompressed(CONTENT).unwrap(), CONTENT);
+        assert_eq!(read_decompressed(&b"{}"[..]).unwrap(), b"{}");
+        assert!(read_decompressed(&b""[..]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_concatenated_gzip_members() {
+        // logrotate などで連結された gzip も最後まで読む
+        let mut compressed = Vec::new();
+        for part in [&b"first\n"[..], &b"second\n"[..]] {
+            let mut gzip =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            gzip.write_all(part).unwrap();
+            compressed.extend(gzip.finish().unwrap());
+        }
+
+        assert_eq!(
+            read_decompressed(compressed.as_slice()).unwrap(),
+            b"first\nsecond\n"
+        );
+    }
+
+    #[test]
+    fn test_corrupted_input() {
+        let result = read_decompressed(&[0x1F, 0x8B, 0x00, 0x01, 0x02][..]);
+        assert!(matches!(result, Err(Error::Decompression(_))));
+    }
+
+    #[test]
+    fn test_text_starting_with_magic() {
+        // "BZh" の後にブロックサイズの数字がなければ bzip2 ではない
+        assert_eq!(detect_compression(b"BZh, not compressed"), None);
+        assert_eq!(detect_compression(b"BZh9"), Some(Compression::Bzip2));
+
+        // 展開できないテキストはそのまま読む
+        let text = b"BZh1 is just a log line\n";
+        assert_eq!(detect_compression(text), Some(Compression::Bzip2));
+        assert_eq!(read_decompressed(&text[..]).unwrap(), text);
+    }
+}
@@ -0,0 +1,685 @@
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    SecondsFormat, TimeZone, Timelike, Utc,
+};
+use chrono_tz::Tz;
+use serde_json::Value;
+
+use crate::{Error, parse_operation_arguments, value_to_string};
+
+/// オフセット付きの日時フォーマット（自動判定用）
+const OFFSET_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f %z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%d/%b/%Y:%H:%M:%S %z",
+];
+
+/// オフセットなしの日時フォーマット（UTCとして扱う）
+const NAIVE_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S%.f",
+    "%Y/%m/%d %H:%M",
+];
+
+/// 日付のみのフォーマット（00:00:00 UTCとして扱う）
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d"];
+
+/// 日時操作かどうかを判定
+pub fn is_datetime_operation(operation: &str) -> bool {
+    let name = operation
+        .split_once('(')
+        .map(|(name, _)| name)
+        .unwrap_or(operation);
+
+    let has_valid_args = !operation.contains('(') || operation.ends_with(')');
+
+    has_valid_args
+        && matches!(
+            name,
+            "to_datetime"
+                | "strftime"
+                | "to_epoch"
+                | "from_epoch"
+                | "date_trunc"
+                | "date_diff"
+                | "to_timezone"
+        )
+}
+
+/// 日時操作を適用
+pub fn apply_datetime_operation(value: &Value, operation: &str) -> Result<Value, Error> {
+    let (name, args) = parse_operation(operation)?;
+
+    match (name, args.as_slice()) {
+        ("to_datetime", []) => Ok(datetime_to_value(&parse_datetime_value(value)?)),
+        ("to_datetime", [format]) => {
+            let text = value_as_text(value, "to_datetime")?;
+            let datetime = parse_datetime_with_format(&text, format).ok_or_else(|| {
+                Error::StringOperation(format!(
+                    "to_datetime: '{}' does not match format '{}'",
+                    text, format
+                ))
+            })?;
+            Ok(datetime_to_value(&datetime))
+        }
+        ("strftime", [format]) => {
+            let datetime = parse_datetime_value(value)?;
+            Ok(Value::String(format_datetime(&datetime, format)?))
+        }
+        ("to_epoch", []) => to_epoch(value, "s"),
+        ("to_epoch", [unit]) => to_epoch(value, unit),
+        ("from_epoch", []) => from_epoch(value, "s"),
+        ("from_epoch", [unit]) => from_epoch(value, unit),
+        ("date_trunc", [unit]) => {
+            let datetime = parse_datetime_value(value)?;
+            Ok(datetime_to_value(&truncate_datetime(&datetime, unit)?))
+        }
+        ("date_diff", [end, start, unit]) => {
+            let end = resolve_datetime_argument(value, end)?;
+            let start = resolve_datetime_argument(value, start)?;
+            duration_in_unit(end - start, unit)
+        }
+        ("date_diff", [start, unit]) => {
+            let end = parse_datetime_value(value)?;
+            let start = resolve_datetime_argument(value, start)?;
+            duration_in_unit(end - start, unit)
+        }
+        ("to_timezone", [zone]) => {
+            let datetime = parse_datetime_value(value)?;
+            Ok(datetime_to_value(&convert_timezone(&datetime, zone)?))
+        }
+        _ => Err(Error::StringOperation(format!(
+            "Invalid arguments for {}: {}",
+            name, operation
+        ))),
+    }
+}
+
+/// 日時文字列同士を時系列で比較（どちらかが日時でなければNone）
+pub fn compare_datetime_strings(a: &str, b: &str) -> Option<Ordering> {
+    let a = parse_datetime(a)?;
+    let b = parse_datetime(b)?;
+    Some(a.cmp(&b))
+}
+
+/// ソート用に正規化した日時のフォーマット（UTC・固定幅なので辞書順が時系列順になる）
+const SORTABLE_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.9f";
+
+/// ソートキーの列を、日時として並べられる場合だけ正規化した文字列に置き換える
+///
+/// null 以外の全ての値が日時文字列として解釈できる場合のみ置き換える。1つでも
+/// 日時でない値があれば列全体を元の値のまま（文字列は辞書順で）比較する。
+/// 比較のたびにパースせず、ソート前に一度だけ呼び出す。
+pub fn normalize_datetime_sort_keys<'a>(column: impl IntoIterator<Item = &'a mut Value>) {
+    let mut column: Vec<&mut Value> = column.into_iter().collect();
+
+    let mut datetimes = Vec::with_capacity(column.len());
+    for value in &column {
+        match &**value {
+            Value::Null => datetimes.push(None),
+            Value::String(s) => match parse_datetime(s) {
+                Some(datetime) => datetimes.push(Some(datetime)),
+                None => return,
+            },
+            _ => return,
+        }
+    }
+
+    for (value, datetime) in column.iter_mut().zip(datetimes) {
+        if let Some(datetime) = datetime {
+            **value = Value::String(
+                datetime
+                    .naive_utc()
+                    .format(SORTABLE_DATETIME_FORMAT)
+                    .to_string(),
+            );
+        }
+    }
+}
+
+/// 日時文字列を自動判定でパース（ISO-8601, RFC 2822, Apache形式など）
+pub fn parse_datetime(text: &str) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+
+    // 数字で始まらない文字列はRFC 2822（"Tue, 1 Jul 2003 ..."）のみ対象
+    if !text.starts_with(|c: char| c.is_ascii_digit()) {
+        return DateTime::parse_from_rfc2822(text).ok();
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime);
+    }
+
+    OFFSET_FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NAIVE_DATETIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                .map(|naive| naive.and_utc().fixed_offset())
+        })
+        .or_else(|| {
+            DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc().fixed_offset())
+        })
+}
+
+/// 指定フォーマットで日時をパース（オフセット付き → 日時 → 日付の順に試行）
+fn parse_datetime_with_format(text: &str, format: &str) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+
+    DateTime::parse_from_str(text, format)
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(text, format)
+                .ok()
+                .map(|naive| naive.and_utc().fixed_offset())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, format)
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc().fixed_offset())
+        })
+}
+
+/// 値から日時を取得（文字列は自動判定、数値はエポック秒）
+fn parse_datetime_value(value: &Value) -> Result<DateTime<FixedOffset>, Error> {
+    match value {
+        Value::String(s) => parse_datetime(s).ok_or_else(|| {
+            Error::StringOperation(format!("Unrecognized date/time format: '{}'", s))
+        }),
+        Value::Number(_) => epoch_to_datetime(value, "s"),
+        _ => Err(Error::StringOperation(format!(
+            "Date/time operations can only be applied to strings or numbers, got: {}",
+            value_to_string(value)
+        ))),
+    }
+}
+
+/// date_diff の引数を解決（.field は現在のオブジェクトから、それ以外は日時リテラル）
+fn resolve_datetime_argument(
+    value: &Value,
+    argument: &str,
+) -> Result<DateTime<FixedOffset>, Error> {
+    if argument.starts_with('.') {
+        let field_value = get_field_by_path(value, argument).ok_or_else(|| {
+            Error::StringOperation(format!("date_diff: field '{}' not found", argument))
+        })?;
+        parse_datetime_value(field_value)
+    } else {
+        parse_datetime(argument).ok_or_else(|| {
+            Error::StringOperation(format!("Unrecognized date/time format: '{}'", argument))
+        })
+    }
+}
+
+/// RFC 3339 文字列として出力（UTCは "Z"）
+fn datetime_to_value(datetime: &DateTime<FixedOffset>) -> Value {
+    Value::String(datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+/// strftime形式でフォーマット（不正な指定子はエラー）
+fn format_datetime(datetime: &DateTime<FixedOffset>, format: &str) -> Result<String, Error> {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return Err(Error::StringOperation(format!(
+            "Invalid strftime format: '{}'",
+            format
+        )));
+    }
+
+    let mut output = String::new();
+    write!(output, "{}", datetime.format_with_items(items.into_iter())).map_err(|_| {
+        Error::StringOperation(format!("Failed to format date/time with '{}'", format))
+    })?;
+    Ok(output)
+}
+
+fn to_epoch(value: &Value, unit: &str) -> Result<Value, Error> {
+    let datetime = parse_datetime_value(value)?;
+    let epoch = match unit {
+        "s" => datetime.timestamp(),
+        "ms" => datetime.timestamp_millis(),
+        _ => return Err(invalid_unit("to_epoch", unit)),
+    };
+    Ok(Value::Number(epoch.into()))
+}
+
+fn from_epoch(value: &Value, unit: &str) -> Result<Value, Error> {
+    Ok(datetime_to_value(&epoch_to_datetime(value, unit)?))
+}
+
+/// エポック値（数値または数値文字列）を日時に変換
+fn epoch_to_datetime(value: &Value, unit: &str) -> Result<DateTime<FixedOffset>, Error> {
+    let epoch = match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        Error::StringOperation(format!(
+            "Epoch value must be a number, got: {}",
+            value_to_string(value)
+        ))
+    })?;
+
+    let millis = match unit {
+        "s" => epoch * 1000.0,
+        "ms" => epoch,
+        _ => return Err(invalid_unit("from_epoch", unit)),
+    };
+
+    DateTime::<Utc>::from_timestamp_millis(millis.round() as i64)
+        .map(|datetime| datetime.fixed_offset())
+        .ok_or_else(|| Error::StringOperation(format!("Epoch value out of range: {}", epoch)))
+}
+
+/// 日時を単位の先頭に切り捨て（タイムゾーンは元のオフセットのまま）
+fn truncate_datetime(
+    datetime: &DateTime<FixedOffset>,
+    unit: &str,
+) -> Result<DateTime<FixedOffset>, Error> {
+    let local = datetime.naive_local();
+    let date = local.date();
+
+    let truncated = match unit {
+        "second" => local.with_nanosecond(0),
+        "minute" => local.with_nanosecond(0).and_then(|t| t.with_second(0)),
+        "hour" => date.and_hms_opt(local.hour(), 0, 0),
+        "day" => date.and_hms_opt(0, 0, 0),
+        "week" => {
+            // 週の始まりは月曜日
+            let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+            monday.and_hms_opt(0, 0, 0)
+        }
+        "month" => date.with_day(1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+        _ => return Err(invalid_unit("date_trunc", unit)),
+    };
+
+    truncated
+        .and_then(|naive| datetime.offset().from_local_datetime(&naive).single())
+        .ok_or_else(|| Error::StringOperation(format!("date_trunc failed for unit '{}'", unit)))
+}
+
+/// 差分を指定単位で返す（割り切れる場合は整数）
+fn duration_in_unit(duration: Duration, unit: &str) -> Result<Value, Error> {
+    let unit_millis: i64 = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" | "min" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 604_800_000,
+        _ => return Err(invalid_unit("date_diff", unit)),
+    };
+
+    let millis = duration.num_milliseconds();
+    if millis % unit_millis == 0 {
+        Ok(Value::Number((millis / unit_millis).into()))
+    } else {
+        let value = millis as f64 / unit_millis as f64;
+        Ok(serde_json::Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null))
+    }
+}
+
+/// タイムゾーン変換（IANA名, "+09:00" 形式, "UTC", "local"）
+fn convert_timezone(
+    datetime: &DateTime<FixedOffset>,
+    zone: &str,
+) -> Result<DateTime<FixedOffset>, Error> {
+    if zone.eq_ignore_ascii_case("utc") || zone == "Z" {
+        return Ok(datetime.with_timezone(&Utc).fixed_offset());
+    }
+
+    if zone.eq_ignore_ascii_case("local") {
+        return Ok(datetime.with_timezone(&Local).fixed_offset());
+    }
+
+    if let Some(offset) = parse_fixed_offset(zone) {
+        return Ok(datetime.with_timezone(&offset));
+    }
+
+    let tz: Tz = zone
+        .parse()
+        .map_err(|_| Error::StringOperation(format!("Unknown timezone: '{}'", zone)))?;
+    let converted = datetime.with_timezone(&tz);
+    Ok(converted.with_timezone(&converted.offset().fix()))
+}
+
+/// "+09:00" / "-0530" 形式のオフセットを解析
+fn parse_fixed_offset(zone: &str) -> Option<FixedOffset> {
+    let sign = match zone.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+
+    let digits: String = zone[1..].chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// 値をテキストとして取得（数値も文字列化して受け付ける）
+fn value_as_text(value: &Value, operation: &str) -> Result<String, Error> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        _ => Err(Error::StringOperation(format!(
+            "{} can only be applied to strings, got: {}",
+            operation,
+            value_to_string(value)
+        ))),
+    }
+}
+
+fn invalid_unit(operation: &str, unit: &str) -> Error {
+    Error::StringOperation(format!("{}: unsupported unit '{}'", operation, unit))
+}
+
+/// resample で生成できるバケット数の上限
+const MAX_RESAMPLE_BUCKETS: i64 = 1_000_000;
+
+/// 時刻フィールドで固定間隔のバケットに分割（空のバケットも含む）
+///
+/// 結果は group_by と同じ `{"group": バケット開始時刻, "items": [...]}` 形式。
+/// バケットは最も早い時刻のタイムゾーンで揃える。
+pub fn apply_resample(
+    data: Vec<Value>,
+    field_path: &str,
+    interval: &str,
+) -> Result<Vec<Value>, Error> {
+    let interval_millis = parse_interval(interval)?;
+
+    let mut records = Vec::new();
+    for item in data {
+        let timestamp = match get_field_by_path(&item, field_path) {
+            None | Some(Value::Null) => continue, // 時刻のないレコードは対象外
+            Some(value) => parse_datetime_value(value)
+                .map_err(|e| Error::InvalidQuery(format!("resample: {}", e)))?,
+        };
+        records.push((timestamp, item));
+    }
+
+    let Some(earliest) = records.iter().map(|(timestamp, _)| *timestamp).min() else {
+        return Ok(Vec::new());
+    };
+    let offset = *earliest.offset();
+    let offset_millis = offset.local_minus_utc() as i64 * 1000;
+
+    let bucket_index = |timestamp: &DateTime<FixedOffset>| {
+        (timestamp.timestamp_millis() + offset_millis).div_euclid(interval_millis)
+    };
+
+    let first_bucket = bucket_index(&earliest);
+    let last_bucket = records
+        .iter()
+        .map(|(timestamp, _)| bucket_index(timestamp))
+        .max()
+        .unwrap_or(first_bucket);
+
+    let bucket_count = last_bucket - first_bucket + 1;
+    if bucket_count > MAX_RESAMPLE_BUCKETS {
+        return Err(Error::InvalidQuery(format!(
+            "resample would create {} buckets (limit {}). Use a larger interval than '{}'",
+            bucket_count, MAX_RESAMPLE_BUCKETS, interval
+        )));
+    }
+
+    let mut buckets: Vec<Vec<Value>> = vec![Vec::new(); bucket_count as usize];
+    for (timestamp, item) in records {
+        buckets[(bucket_index(&timestamp) - first_bucket) as usize].push(item);
+    }
+
+    buckets
+        .into_iter()
+        .enumerate()
+        .map(|(i, items)| {
+            let start_millis = (first_bucket + i as i64) * interval_millis - offset_millis;
+            let start = DateTime::<Utc>::from_timestamp_millis(start_millis)
+                .ok_or_else(|| Error::InvalidQuery("resample: timestamp out of range".to_string()))?
+                .with_timezone(&offset);
+
+            let mut group_obj = serde_json::Map::new();
+            group_obj.insert("group".to_string(), datetime_to_value(&start));
+            group_obj.insert("items".to_string(), Value::Array(items));
+            Ok(Value::Object(group_obj))
+        })
+        .collect()
+}
+
+/// "30s", "5m", "1h", "1d", "1w", "500ms" をミリ秒に変換
+fn parse_interval(interval: &str) -> Result<i64, Error> {
+    let interval = interval.trim();
+    let split_pos = interval
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(interval.len());
+    let (amount, unit) = interval.split_at(split_pos);
+
+    let unit_millis: i64 = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" | "min" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 604_800_000,
+        _ => 0,
+    };
+
+    match amount.parse::<i64>() {
+        Ok(amount) if amount > 0 && unit_millis > 0 => {
+            amount.checked_mul(unit_millis).ok_or_else(|| {
+                Error::InvalidQuery(format!("Resample interval too large: '{}'", interval))
+            })
+        }
+        _ => Err(Error::InvalidQuery(format!(
+            "Invalid resample interval: '{}'. Use a number with ms, s, m, h, d or w (e.g. \"5m\")",
+            interval
+        ))),
+    }
+}
+
+/// ".a.b" 形式のパスで値を取得
+fn get_field_by_path<'a>(value: &'a Value, field_path: &str) -> Option<&'a Value> {
+    field_path
+        .trim_start_matches('.')
+        .split('.')
+        .filter(|field| !field.is_empty())
+        .try_fold(value, |current, field| current.get(field))
+}
+
+/// "name(arg1, \"arg, 2\")" を名前と引数リストに分解
+fn parse_operation(operation: &str) -> Result<(&str, Vec<String>), Error> {
+    let name = operation
+        .split_once('(')
+        .map(|(name, _)| name)
+        .unwrap_or(operation);
+
+    let args = if operation.contains('(') {
+        parse_operation_arguments(operation)?
+    } else {
+        Vec::new()
+    };
+
+    Ok((name.trim(), args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_format() {
+        let value = json!("2024-03-15 14:30:45");
+
+        let result = apply_datetime_operation(&value, "to_datetime").unwrap();
+        assert_eq!(result, json!("2024-03-15T14:30:45Z"));
+
+        let result = apply_datetime_operation(
+            &json!("15/03/2024 14:30"),
+            r#"to_datetime("%d/%m/%Y %H:%M")"#,
+        )
+        .unwrap();
+        assert_eq!(result, json!("2024-03-15T14:30:00Z"));
+
+        let result = apply_datetime_operation(&value, r#"strftime("%Y/%m/%d, %H時")"#).unwrap();
+        assert_eq!(result, json!("2024/03/15, 14時"));
+
+        assert!(apply_datetime_operation(&json!("yesterday"), "to_datetime").is_err());
+        assert!(apply_datetime_operation(&value, r#"strftime("%Q")"#).is_err());
+    }
+
+    #[test]
+    fn test_epoch_conversion() {
+        let value = json!("2024-01-01T00:00:00+09:00");
+
+        let result = apply_datetime_operation(&value, "to_epoch").unwrap();
+        assert_eq!(result, json!(1704034800));
+
+        let result = apply_datetime_operation(&value, r#"to_epoch("ms")"#).unwrap();
+        assert_eq!(result, json!(1704034800000i64));
+
+        let result = apply_datetime_operation(&json!(1704034800), "from_epoch").unwrap();
+        assert_eq!(result, json!("2023-12-31T15:00:00Z"));
+
+        let result = apply_datetime_operation(&json!(1500), r#"from_epoch("ms")"#).unwrap();
+        assert_eq!(result, json!("1970-01-01T00:00:01.500Z"));
+    }
+
+    #[test]
+    fn test_trunc_diff_and_timezone() {
+        let value = json!("2024-03-14T14:35:12+09:00");
+
+        let result = apply_datetime_operation(&value, r#"date_trunc("hour")"#).unwrap();
+        assert_eq!(result, json!("2024-03-14T14:00:00+09:00"));
+
+        let result = apply_datetime_operation(&value, r#"date_trunc("week")"#).unwrap();
+        assert_eq!(result, json!("2024-03-11T00:00:00+09:00"));
+
+        let item = json!({"start": "2024-03-14 10:00:00", "end": "2024-03-14 11:30:00"});
+        let result = apply_datetime_operation(&item, r#"date_diff(.end, .start, "s")"#).unwrap();
+        assert_eq!(result, json!(5400));
+        let result = apply_datetime_operation(&item, r#"date_diff(.end, .start, "h")"#).unwrap();
+        assert_eq!(result, json!(1.5));
+
+        let result = apply_datetime_operation(&value, r#"to_timezone("UTC")"#).unwrap();
+        assert_eq!(result, json!("2024-03-14T05:35:12Z"));
+
+        let result = apply_datetime_operation(
+            &json!("2024-07-01T12:00:00Z"),
+            r#"to_timezone("America/New_York")"#,
+        )
+        .unwrap();
+        assert_eq!(result, json!("2024-07-01T08:00:00-04:00"));
+
+        assert!(apply_datetime_operation(&value, r#"to_timezone("Mars/Base")"#).is_err());
+    }
+
+    #[test]
+    fn test_compare_datetime_strings() {
+        assert_eq!(
+            compare_datetime_strings("2024-01-01T08:00:00+09:00", "2023-12-31T23:30:00Z"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_datetime_strings("2024-01-02", "2024-01-01 23:59:59"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare_datetime_strings("apple", "2024-01-01"), None);
+    }
+
+    #[test]
+    fn test_normalize_datetime_sort_keys() {
+        let mut keys = vec![
+            json!("2024-01-01T08:00:00+09:00"),
+            Value::Null,
+            json!("2023-12-31 23:30:00"),
+        ];
+        normalize_datetime_sort_keys(keys.iter_mut());
+        assert_eq!(
+            keys,
+            vec![
+                json!("2023-12-31T23:00:00.000000000"),
+                Value::Null,
+                json!("2023-12-31T23:30:00.000000000"),
+            ]
+        );
+
+        // 日時でない値が混ざる列はそのまま
+        let mut keys = vec![json!("2024-01-01"), json!("pending"), json!(3)];
+        let original = keys.clone();
+        normalize_datetime_sort_keys(keys.iter_mut());
+        assert_eq!(keys, original);
+    }
+
+    #[test]
+    fn test_resample_buckets() {
+        let data = vec![
+            json!({"ts": "2024-03-14T10:01:00Z", "cpu": 10}),
+            json!({"ts": "2024-03-14T10:03:30Z", "cpu": 30}),
+            json!({"ts": "2024-03-14T10:12:00Z", "cpu": 50}),
+            json!({"ts": null, "cpu": 99}),
+        ];
+
+        let result = apply_resample(data, ".ts", "5m").unwrap();
+        let groups: Vec<&str> = result
+            .iter()
+            .map(|g| g["group"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                "2024-03-14T10:00:00Z",
+                "2024-03-14T10:05:00Z",
+                "2024-03-14T10:10:00Z"
+            ]
+        );
+
+        let sizes: Vec<usize> = result
+            .iter()
+            .map(|g| g["items"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(sizes, vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_resample_aligns_to_data_timezone() {
+        let data = vec![
+            json!({"ts": "2024-03-14T23:30:00+09:00"}),
+            json!({"ts": "2024-03-15T08:00:00+09:00"}),
+        ];
+
+        let result = apply_resample(data, ".ts", "1d").unwrap();
+        let groups: Vec<&str> = result
+            .iter()
+            .map(|g| g["group"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            groups,
+            vec!["2024-03-14T00:00:00+09:00", "2024-03-15T00:00:00+09:00"]
+        );
+
+        assert!(apply_resample(vec![], ".ts", "5x").is_err());
+        assert!(apply_resample(vec![], ".ts", "99999999999999999w").is_err());
+        assert!(apply_resample(vec![json!({"ts": "soon"})], ".ts", "1h").is_err());
+    }
+}
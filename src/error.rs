@@ -14,6 +14,29 @@ pub enum Error {
     #[error("YAML deserialization error: {0}")]
     Yaml(#[from] serde_yaml::Error),
 
+    #[error("TOML deserialization error: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("TOML serialization error: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    #[error("XML parsing error: {0}")]
+    Xml(String),
+
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "parquet")]
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    #[error("Spreadsheet error: {0}")]
+    Spreadsheet(#[from] calamine::Error),
+
+    #[error("Decompression error: {0}")]
+    Decompression(String),
+
     #[error("CSV parsing error: {0}")]
     Csv(#[from] csv::Error),
 
@@ -31,4 +54,7 @@ pub enum Error {
 
     #[error("String operation error: {0}")]
     StringOperation(String),
+
+    #[error("Numeric operation error: {0}")]
+    NumericOperation(String),
 }
@@ -414,19 +414,15 @@ pub fn handle_array_access_as_json(
             for field in &fields {
                 if field.contains('[') && field.contains(']') {
                     // 配列アクセスの場合
-                    if let Ok((idx, ridx)) = parse_array_segment(field) {
-                        if let Some(field_key) = field.get(..idx) {
-                            if let Some(index_str) = field.get(idx + 1..ridx) {
-                                if let Ok(field_index) = index_str.parse::<usize>() {
-                                    if let Some(array) = current.get(field_key) {
-                                        if let Some(item) = array.get(field_index) {
-                                            current = item;
-                                            continue;
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                    if let Ok((idx, ridx)) = parse_array_segment(field)
+                        && let Some(field_key) = field.get(..idx)
+                        && let Some(index_str) = field.get(idx + 1..ridx)
+                        && let Ok(field_index) = index_str.parse::<usize>()
+                        && let Some(array) = current.get(field_key)
+                        && let Some(item) = array.get(field_index)
+                    {
+                        current = item;
+                        continue;
                     }
                     // エラーの場合はこの要素をスキップ
                     return None;
@@ -513,19 +509,15 @@ pub fn handle_array_access(
             for field in &fields {
                 if field.contains('[') && field.contains(']') {
                     // 配列アクセスの場合
-                    if let Ok((idx, ridx)) = parse_array_segment(field) {
-                        if let Some(field_key) = field.get(..idx) {
-                            if let Some(index_str) = field.get(idx + 1..ridx) {
-                                if let Ok(field_index) = index_str.parse::<usize>() {
-                                    if let Some(array) = current.get(field_key) {
-                                        if let Some(item) = array.get(field_index) {
-                                            current = item;
-                                            continue;
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                    if let Ok((idx, ridx)) = parse_array_segment(field)
+                        && let Some(field_key) = field.get(..idx)
+                        && let Some(index_str) = field.get(idx + 1..ridx)
+                        && let Ok(field_index) = index_str.parse::<usize>()
+                        && let Some(array) = current.get(field_key)
+                        && let Some(item) = array.get(field_index)
+                    {
+                        current = item;
+                        continue;
                     }
                     // エラーの場合はこの要素をスキップ
                     return None;
@@ -551,10 +543,29 @@ fn split_pipeline_respecting_parentheses(query: &str) -> Result<Vec<String>, Err
     let mut parts = Vec::new();
     let mut current_part = String::new();
     let mut paren_depth = 0;
+    // 現在の引用符（" または '）
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
     let chars = query.chars().peekable();
 
     for ch in chars {
+        // 引用符内の括弧やパイプは区切りとして扱わない
+        if let Some(q) = quote {
+            current_part.push(ch);
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                c if c == q => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+
         match ch {
+            '"' | '\'' => {
+                quote = Some(ch);
+                current_part.push(ch);
+            }
             '(' => {
                 paren_depth += 1;
                 current_part.push(ch);
@@ -806,6 +817,19 @@ mod tests {
             _ => panic!("Expected InvalidQuery error"),
         }
     }
+
+    #[test]
+    fn test_split_pipeline_respects_quotes() {
+        let parts =
+            split_pipeline_respecting_parentheses(r#".[] | map(.a | split('|')) | split("|)")"#)
+                .unwrap();
+        assert_eq!(parts, vec![".[]", "map(.a | split('|'))", r#"split("|)")"#]);
+
+        // 別種の引用符は文字列の一部として扱う
+        let parts =
+            split_pipeline_respecting_parentheses(r#".[] | replace("it's", "x") | count"#).unwrap();
+        assert_eq!(parts, vec![".[]", r#"replace("it's", "x")"#, "count"]);
+    }
 }
 
 pub fn test_handle_array_access_normal_case() {
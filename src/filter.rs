@@ -1,6 +1,11 @@
 use serde_json::Value;
 
-use crate::{Error, apply_stats_operation, print_data_info, string_ops, value_to_string};
+use crate::{
+    Error, apply_distinct_by, apply_duplicates, apply_key_value_operation, apply_object_operation,
+    apply_parse_operation, apply_resample, apply_stats_operation, compare_datetime_strings,
+    extract_parse_pattern, is_object_operation, normalize_datetime_sort_keys, parse_kv_separators,
+    print_data_info, string_ops, value_to_string,
+};
 
 pub fn apply_simple_filter(data: Vec<Value>, filter: &str) -> Result<Vec<Value>, Error> {
     if filter.starts_with("select(") && filter.ends_with(")") {
@@ -36,18 +41,26 @@ fn apply_filter_with_string_operations(
     }
 
     let field_access = parts[0];
-    let string_operations: Vec<&str> = parts[1..].to_vec();
+    let mut string_operations: Vec<&str> = parts[1..].to_vec();
 
     // 最後の操作は比較操作である必要がある
-    let last_operation = string_operations
+    let last_operation = *string_operations
         .last()
         .ok_or_else(|| Error::InvalidQuery("Missing comparison operation".to_string()))?;
 
-    if !is_comparison_operation(last_operation) {
-        return Err(Error::InvalidQuery(
-            "Last operation must be a comparison".to_string(),
-        ));
-    }
+    // "type == \"number\"" のように末尾に比較演算子が付いている場合は分離する
+    let trailing_comparison = if is_comparison_operation(last_operation) {
+        None
+    } else {
+        let (operation, operator, target) = parse_condition(last_operation)
+            .map_err(|_| Error::InvalidQuery("Last operation must be a comparison".to_string()))?;
+
+        string_operations.pop();
+        if !operation.is_empty() {
+            string_operations.push(&last_operation[..operation.len()]);
+        }
+        Some((operator, target))
+    };
 
     let mut results = Vec::new();
 
@@ -59,7 +72,10 @@ fn apply_filter_with_string_operations(
         let final_value = string_ops::apply_string_pipeline(&field_value, &string_operations)?;
 
         // 比較結果を評価し、not演算子を適用
-        let condition_result = matches!(final_value, Value::Bool(true));
+        let condition_result = match &trailing_comparison {
+            Some((operator, target)) => compare_values(&final_value, operator, target),
+            None => matches!(final_value, Value::Bool(true)),
+        };
         let final_result = if is_negated {
             !condition_result
         } else {
@@ -110,8 +126,8 @@ fn apply_existing_simple_filter(data: Vec<Value>, condition: &str) -> Result<Vec
 fn parse_not_condition_with_parentheses(condition: &str) -> Result<(String, bool), Error> {
     let trimmed = condition.trim();
 
-    if trimmed.starts_with("not ") {
-        let rest = trimmed[4..].trim();
+    if let Some(rest) = trimmed.strip_prefix("not ") {
+        let rest = rest.trim();
 
         // 括弧で囲まれているかチェック
         if rest.starts_with('(') && rest.ends_with(')') {
@@ -417,6 +433,29 @@ pub fn apply_pipeline_operation(data: Vec<Value>, operation: &str) -> Result<Vec
 
         let grouped = group_data_by_field(data, field_name)?;
         Ok(grouped)
+    } else if trimmed_op.starts_with("parse(") && trimmed_op.ends_with(")") {
+        // parse("%{IP:client} ...") でテキスト行を構造化
+        let pattern = extract_parse_pattern(&trimmed_op[6..trimmed_op.len() - 1])?;
+        apply_parse_operation(data, &pattern)
+    } else if trimmed_op == "logfmt"
+        || trimmed_op == "kv"
+        || (trimmed_op.starts_with("kv(") && trimmed_op.ends_with(")"))
+    {
+        // key=value 形式のテキスト行を構造化
+        let (pair_separator, kv_separator) = parse_kv_separators(trimmed_op)?;
+        apply_key_value_operation(data, pair_separator.as_deref(), &kv_separator)
+    } else if trimmed_op.starts_with("resample(") && trimmed_op.ends_with(")") {
+        // resample(.timestamp, "5m") の処理
+        let args = &trimmed_op[9..trimmed_op.len() - 1];
+        let (field, interval) = args.split_once(',').ok_or_else(|| {
+            Error::InvalidQuery(
+                "resample requires a field and an interval: resample(.timestamp, \"5m\")"
+                    .to_string(),
+            )
+        })?;
+        let interval = interval.trim().trim_matches('"').trim_matches('\'');
+
+        apply_resample(data, field.trim(), interval)
     } else if trimmed_op == "unique" {
         // unique操作（重複除去）
         let result = apply_stats_operation(&data, "unique", None)?;
@@ -471,6 +510,57 @@ pub fn apply_pipeline_operation(data: Vec<Value>, operation: &str) -> Result<Vec
         let field_name = field.trim_start_matches('.');
         let result = apply_stats_operation(&data, "stddev", Some(field_name))?;
         Ok(vec![result])
+    } else if trimmed_op.starts_with("top(") && trimmed_op.ends_with(")") {
+        // top(n, .field) - 上位N件のレコード
+        let spec = &trimmed_op[4..trimmed_op.len() - 1];
+        apply_top_operation(data, spec, true)
+    } else if trimmed_op.starts_with("bottom(") && trimmed_op.ends_with(")") {
+        // bottom(n, .field) - 下位N件のレコード
+        let spec = &trimmed_op[7..trimmed_op.len() - 1];
+        apply_top_operation(data, spec, false)
+    } else if trimmed_op.starts_with("max_by(") && trimmed_op.ends_with(")") {
+        // max_by(.field) - 最大値を持つレコード全体
+        let field = trimmed_op[7..trimmed_op.len() - 1].trim();
+        apply_extreme_by_operation(data, field, true)
+    } else if trimmed_op.starts_with("min_by(") && trimmed_op.ends_with(")") {
+        // min_by(.field) - 最小値を持つレコード全体
+        let field = trimmed_op[7..trimmed_op.len() - 1].trim();
+        apply_extreme_by_operation(data, field, false)
+    } else if trimmed_op.starts_with("explode(") && trimmed_op.ends_with(")") {
+        // explode(.field) - 配列フィールドを要素ごとのレコードに展開
+        let field = trimmed_op[8..trimmed_op.len() - 1].trim();
+        apply_explode_operation(data, field)
+    } else if trimmed_op == "flatten" {
+        // flatten - 配列の配列を1階層展開
+        Ok(flatten_values(data, 1))
+    } else if trimmed_op.starts_with("flatten(") && trimmed_op.ends_with(")") {
+        // flatten(depth) - 指定した階層まで展開
+        let depth_str = trimmed_op[8..trimmed_op.len() - 1].trim();
+        let depth = depth_str
+            .parse::<usize>()
+            .map_err(|_| Error::InvalidQuery(format!("Invalid flatten depth: {}", depth_str)))?;
+        Ok(flatten_values(data, depth))
+    } else if trimmed_op.starts_with("distinct_by(") && trimmed_op.ends_with(")") {
+        // distinct_by(.field1, .field2) - レコード全体の重複除去
+        let spec = &trimmed_op[12..trimmed_op.len() - 1];
+        let (fields, keep) = parse_distinct_spec(spec, "distinct_by")?;
+        Ok(apply_distinct_by(data, &fields, keep.unwrap_or(false)))
+    } else if trimmed_op.starts_with("duplicates(") && trimmed_op.ends_with(")") {
+        // duplicates(.field) - 重複しているレコードのみ
+        let spec = &trimmed_op[11..trimmed_op.len() - 1];
+        let (fields, keep) = parse_distinct_spec(spec, "duplicates")?;
+        if keep.is_some() {
+            return Err(Error::InvalidQuery(
+                "duplicates only accepts fields: duplicates(.field1, .field2)".to_string(),
+            ));
+        }
+        Ok(apply_duplicates(data, &fields))
+    } else if is_object_operation(trimmed_op) {
+        // オブジェクト操作（各要素に適用）
+        apply_object_operation(data, trimmed_op)
+    } else if trimmed_op.starts_with("unnest(") && trimmed_op.ends_with(")") {
+        // unnest(.field) - 子オブジェクトのフィールドを親にマージ
+        apply_unnest_operation(data, trimmed_op)
     } else {
         // より詳細なエラーメッセージ
         Err(Error::InvalidQuery(format!(
@@ -732,6 +822,310 @@ pub fn apply_pipeline_operation(data: Vec<Value>, operation: &str) -> Result<Vec
 // }
 //
 
+/// top(n, .field) / bottom(n, .field) の処理（グループ化データ対応）
+fn apply_top_operation(data: Vec<Value>, spec: &str, is_top: bool) -> Result<Vec<Value>, Error> {
+    let (count, field_path) = parse_top_spec(spec)?;
+
+    if is_grouped_data(&data) {
+        // 各グループの items を上位N件に絞る
+        Ok(data
+            .into_iter()
+            .map(|group| {
+                map_group_items(group, |items| {
+                    select_ranked_records(items, count, &field_path, is_top)
+                })
+            })
+            .collect())
+    } else {
+        Ok(select_ranked_records(data, count, &field_path, is_top))
+    }
+}
+
+/// min_by(.field) / max_by(.field) の処理（グループ化データ対応）
+fn apply_extreme_by_operation(
+    data: Vec<Value>,
+    field_path: &str,
+    is_max: bool,
+) -> Result<Vec<Value>, Error> {
+    if !field_path.starts_with('.') {
+        return Err(Error::InvalidQuery(format!(
+            "Field path must start with '.': {}",
+            field_path
+        )));
+    }
+
+    if is_grouped_data(&data) {
+        // 各グループの items を1件に絞る
+        Ok(data
+            .into_iter()
+            .map(|group| {
+                map_group_items(group, |items| {
+                    select_ranked_records(items, 1, field_path, is_max)
+                })
+            })
+            .collect())
+    } else {
+        Ok(select_ranked_records(data, 1, field_path, is_max))
+    }
+}
+
+/// top/bottom の引数を解析（例: "3, .revenue" → (3, ".revenue")）
+fn parse_top_spec(spec: &str) -> Result<(usize, String), Error> {
+    let parts: Vec<&str> = spec.split(',').map(|s| s.trim()).collect();
+
+    let count = parts[0].parse::<usize>().map_err(|_| {
+        Error::InvalidQuery(format!(
+            "Invalid count: '{}'. Use: top(n, .field) or bottom(n, .field)",
+            parts[0]
+        ))
+    })?;
+
+    match parts.len() {
+        // フィールド指定なし：値そのもので順位付け
+        1 => Ok((count, ".".to_string())),
+        2 if parts[1].starts_with('.') => Ok((count, parts[1].to_string())),
+        _ => Err(Error::InvalidQuery(
+            "Invalid top/bottom specification. Use: top(n, .field) or bottom(n, .field)"
+                .to_string(),
+        )),
+    }
+}
+
+/// explode(.field) の処理：配列要素ごとに親レコードを複製
+fn apply_explode_operation(data: Vec<Value>, field_path: &str) -> Result<Vec<Value>, Error> {
+    let field_name = parse_reshape_field(field_path)?;
+    let mut results = Vec::new();
+
+    for item in data {
+        match item.get(field_name) {
+            Some(Value::Array(elements)) if elements.is_empty() => {
+                // 空配列はnullとして1行残す
+                results.push(replace_field(&item, field_name, Value::Null));
+            }
+            Some(Value::Array(elements)) => {
+                for element in elements {
+                    results.push(replace_field(&item, field_name, element.clone()));
+                }
+            }
+            // 配列でないフィールドはそのまま
+            _ => results.push(item),
+        }
+    }
+
+    Ok(results)
+}
+
+/// 配列の配列を指定階層まで展開
+fn flatten_values(data: Vec<Value>, depth: usize) -> Vec<Value> {
+    if depth == 0 {
+        return data;
+    }
+
+    let mut flattened = Vec::new();
+    for item in data {
+        match item {
+            Value::Array(arr) => flattened.extend(flatten_values(arr, depth - 1)),
+            _ => flattened.push(item),
+        }
+    }
+
+    flattened
+}
+
+/// unnest(.field) / unnest(.field, "prefix") の処理
+fn apply_unnest_operation(data: Vec<Value>, operation: &str) -> Result<Vec<Value>, Error> {
+    let args = string_ops::parse_operation_arguments(operation)?;
+    if args.is_empty() || args.len() > 2 {
+        return Err(Error::InvalidQuery(
+            "Invalid unnest specification. Use: unnest(.field) or unnest(.field, \"prefix\")"
+                .to_string(),
+        ));
+    }
+
+    let field_name = parse_reshape_field(&args[0])?;
+    // デフォルトのプレフィックスは "field_"
+    let prefix = match args.get(1) {
+        Some(prefix) => prefix.clone(),
+        None => format!("{}_", field_name),
+    };
+
+    let mut results = Vec::new();
+
+    for item in data {
+        match item.get(field_name) {
+            Some(Value::Object(child)) => {
+                results.push(merge_child_fields(&item, field_name, child, &prefix)?);
+            }
+            Some(Value::Array(children)) if children.is_empty() => {
+                results.push(remove_field(&item, field_name));
+            }
+            Some(Value::Array(children)) => {
+                // 配列の場合は要素ごとに1行
+                for child in children {
+                    let row = match child {
+                        Value::Object(child_obj) => {
+                            merge_child_fields(&item, field_name, child_obj, &prefix)?
+                        }
+                        _ => replace_field(&item, field_name, child.clone()),
+                    };
+                    results.push(row);
+                }
+            }
+            _ => results.push(item),
+        }
+    }
+
+    Ok(results)
+}
+
+/// 展開系操作のフィールド指定を検証（".field" → "field"）
+fn parse_reshape_field(field_path: &str) -> Result<&str, Error> {
+    match field_path.strip_prefix('.') {
+        Some(field_name) if !field_name.is_empty() => Ok(field_name),
+        _ => Err(Error::InvalidQuery(format!(
+            "Field path must start with '.': {}",
+            field_path
+        ))),
+    }
+}
+
+/// レコードの指定フィールドを置き換えた複製を作成
+fn replace_field(item: &Value, field_name: &str, new_value: Value) -> Value {
+    let mut updated = item.clone();
+    if let Value::Object(obj) = &mut updated {
+        obj.insert(field_name.to_string(), new_value);
+    }
+    updated
+}
+
+/// レコードから指定フィールドを除いた複製を作成
+fn remove_field(item: &Value, field_name: &str) -> Value {
+    let mut updated = item.clone();
+    if let Value::Object(obj) = &mut updated {
+        obj.shift_remove(field_name);
+    }
+    updated
+}
+
+/// 子オブジェクトのフィールドをプレフィックス付きで親レコードにマージ（元の位置に挿入）
+///
+/// プレフィックス付きのキーが親の既存のキーと重なる場合は上書きせずエラーにする。
+fn merge_child_fields(
+    parent: &Value,
+    field_name: &str,
+    child: &serde_json::Map<String, Value>,
+    prefix: &str,
+) -> Result<Value, Error> {
+    let Value::Object(parent_obj) = parent else {
+        return Ok(parent.clone());
+    };
+
+    let mut merged = serde_json::Map::new();
+    for (key, value) in parent_obj {
+        if key == field_name {
+            for (child_key, child_value) in child {
+                let merged_key = format!("{}{}", prefix, child_key);
+                if merged_key != field_name && parent_obj.contains_key(&merged_key) {
+                    return Err(Error::InvalidQuery(format!(
+                        "unnest(.{}) would overwrite the existing field '{}'; use unnest(.{}, \"prefix\") to rename the child fields",
+                        field_name, merged_key, field_name
+                    )));
+                }
+                merged.insert(merged_key, child_value.clone());
+            }
+        } else {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+
+    Ok(Value::Object(merged))
+}
+
+/// distinct_by / duplicates の引数を解析（例: `.user_id, .day, "last"` → (["user_id", "day"], Some(true))）
+///
+/// 2つ目の戻り値は "first" / "last" の指定（省略時は None）。
+fn parse_distinct_spec(spec: &str, operation: &str) -> Result<(Vec<String>, Option<bool>), Error> {
+    let mut fields = Vec::new();
+    let mut keep_last = None;
+
+    for part in spec.split(',').map(|s| s.trim()) {
+        if let Some(field_name) = part.strip_prefix('.') {
+            fields.push(field_name.to_string());
+            continue;
+        }
+
+        let option = part.trim_matches('"').trim_matches('\'');
+        let value = match option {
+            "first" => false,
+            "last" => true,
+            _ => {
+                return Err(Error::InvalidQuery(format!(
+                    "Invalid {} argument: '{}'. Use fields like .id, optionally followed by \"first\" or \"last\"",
+                    operation, part
+                )));
+            }
+        };
+        if keep_last.replace(value).is_some() {
+            return Err(Error::InvalidQuery(format!(
+                "{} accepts only one \"first\" or \"last\" option",
+                operation
+            )));
+        }
+    }
+
+    if fields.is_empty() {
+        return Err(Error::InvalidQuery(format!(
+            "{} requires at least one field: {}(.field)",
+            operation, operation
+        )));
+    }
+
+    Ok((fields, keep_last))
+}
+
+/// グループの items を置き換え、"group" などの他のキーは保持する
+fn map_group_items(group: Value, f: impl FnOnce(Vec<Value>) -> Vec<Value>) -> Value {
+    match group {
+        Value::Object(mut obj) => {
+            let items = match obj.remove("items") {
+                Some(Value::Array(items)) => items,
+                _ => Vec::new(),
+            };
+            obj.insert("items".to_string(), Value::Array(f(items)));
+            Value::Object(obj)
+        }
+        other => other,
+    }
+}
+
+/// 指定フィールドで順位付けし、先頭N件のレコードを返す（同順位は元の順序を維持）
+fn select_ranked_records(
+    items: Vec<Value>,
+    count: usize,
+    field_path: &str,
+    descending: bool,
+) -> Vec<Value> {
+    // フィールドを持たないレコードは順位付けの対象外
+    let mut candidates: Vec<(Value, Value)> = items
+        .into_iter()
+        .map(|item| (extract_sort_key(&item, field_path), item))
+        .filter(|(key, _)| !key.is_null())
+        .collect();
+    normalize_datetime_sort_keys(candidates.iter_mut().map(|(key, _)| key));
+
+    candidates.sort_by(|(key_a, _), (key_b, _)| {
+        let comparison = compare_sort_values(key_a, key_b);
+        if descending {
+            comparison.reverse()
+        } else {
+            comparison
+        }
+    });
+
+    candidates.truncate(count);
+    candidates.into_iter().map(|(_, item)| item).collect()
+}
+
 /// ソート操作を処理（昇順・降順対応、グループ化データ対応）
 fn apply_sort_operation(data: Vec<Value>, sort_spec: &str) -> Result<Vec<Value>, Error> {
     // グループ化データかどうかをチェック
@@ -744,104 +1138,262 @@ fn apply_sort_operation(data: Vec<Value>, sort_spec: &str) -> Result<Vec<Value>,
 
 /// 通常データのソート処理
 fn apply_sort_to_regular_data(data: Vec<Value>, sort_spec: &str) -> Result<Vec<Value>, Error> {
-    let (field_name, is_descending) = parse_sort_spec(sort_spec)?;
-
-    if field_name.is_empty() {
-        // フィールド指定なし：値そのものでソート
-        let mut sorted_data = data;
-        sorted_data.sort_by(|a, b| {
-            let comparison = compare_sort_values(a, b);
-            if is_descending {
-                comparison.reverse()
-            } else {
-                comparison
+    let sort_keys = parse_sort_spec(sort_spec)?;
+
+    // フィールド指定なしの場合は値そのものでソート
+    Ok(sort_with_keys(data, &sort_keys, |item, key| {
+        extract_sort_key(item, &key.field_path)
+    }))
+}
+
+/// グループ化データのソート処理
+fn apply_sort_to_grouped_data(data: Vec<Value>, sort_spec: &str) -> Result<Vec<Value>, Error> {
+    let sort_keys = parse_sort_spec(sort_spec)?;
+
+    Ok(sort_with_keys(data, &sort_keys, |group, key| {
+        if key.field_path.is_empty() {
+            // フィールド指定なし：グループ名でソート
+            group.get("group").cloned().unwrap_or(Value::Null)
+        } else {
+            // フィールド指定あり：集約結果のフィールドでソート
+            extract_aggregation_value(group, &key.field_path)
+        }
+    }))
+}
+
+/// null値の配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NullPlacement {
+    Default, // 最小値として扱う（昇順で先頭、降順で末尾）
+    First,
+    Last,
+}
+
+/// ソートキーの指定（例: `.salary desc nulls_last`）
+#[derive(Debug, Clone, PartialEq)]
+struct SortKey {
+    field_path: String, // 空の場合は値そのもの
+    descending: bool,
+    nulls: NullPlacement,
+    case_insensitive: bool,
+    natural: bool,
+}
+
+impl SortKey {
+    fn new(field_path: &str) -> Self {
+        Self {
+            field_path: field_path.trim_start_matches('.').to_string(),
+            descending: false,
+            nulls: NullPlacement::Default,
+            case_insensitive: false,
+            natural: false,
+        }
+    }
+}
+
+/// ソートキーの値を事前に抽出し、全キーで安定ソート
+fn sort_with_keys<F>(data: Vec<Value>, sort_keys: &[SortKey], extract: F) -> Vec<Value>
+where
+    F: Fn(&Value, &SortKey) -> Value,
+{
+    let mut decorated: Vec<(Vec<Value>, Value)> = data
+        .into_iter()
+        .map(|item| {
+            let key_values = sort_keys.iter().map(|key| extract(&item, key)).collect();
+            (key_values, item)
+        })
+        .collect();
+
+    // 日時の列はキーごとに一度だけ正規化しておく
+    for index in 0..sort_keys.len() {
+        normalize_datetime_sort_keys(decorated.iter_mut().map(|(values, _)| &mut values[index]));
+    }
+
+    decorated.sort_by(|(values_a, _), (values_b, _)| {
+        compare_with_sort_keys(values_a, values_b, sort_keys)
+    });
+
+    decorated.into_iter().map(|(_, item)| item).collect()
+}
+
+/// 複数キーでの比較（先頭のキーから順に評価）
+fn compare_with_sort_keys(
+    values_a: &[Value],
+    values_b: &[Value],
+    sort_keys: &[SortKey],
+) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    for ((a, b), key) in values_a.iter().zip(values_b).zip(sort_keys) {
+        let comparison = match (key.nulls, a, b) {
+            (NullPlacement::Default, _, _) => compare_collated(a, b, key),
+            (_, Value::Null, Value::Null) => Ordering::Equal,
+            (NullPlacement::First, Value::Null, _) | (NullPlacement::Last, _, Value::Null) => {
+                // null の位置は方向に関係なく固定
+                return Ordering::Less;
             }
-        });
-        Ok(sorted_data)
-    } else {
-        // フィールド指定あり：指定フィールドでソート
-        let mut sorted_data = data;
-        sorted_data.sort_by(|a, b| {
-            let value_a = extract_sort_key(a, &format!(".{}", field_name));
-            let value_b = extract_sort_key(b, &format!(".{}", field_name));
-            let comparison = compare_sort_values(&value_a, &value_b);
-            if is_descending {
-                comparison.reverse()
-            } else {
-                comparison
+            (NullPlacement::First, _, Value::Null) | (NullPlacement::Last, Value::Null, _) => {
+                return Ordering::Greater;
             }
-        });
-        Ok(sorted_data)
+            _ => compare_collated(a, b, key),
+        };
+
+        let comparison = if key.descending {
+            comparison.reverse()
+        } else {
+            comparison
+        };
+
+        if comparison != Ordering::Equal {
+            return comparison;
+        }
     }
+
+    Ordering::Equal
 }
 
-/// グループ化データのソート処理
-fn apply_sort_to_grouped_data(data: Vec<Value>, sort_spec: &str) -> Result<Vec<Value>, Error> {
-    let (field_name, is_descending) = parse_sort_spec(sort_spec)?;
-
-    let mut sorted_data = data;
-
-    if field_name.is_empty() {
-        // フィールド指定なし：グループ名でソート
-        sorted_data.sort_by(|a, b| {
-            let group_a = a.get("group").unwrap_or(&Value::Null);
-            let group_b = b.get("group").unwrap_or(&Value::Null);
-            let comparison = compare_sort_values(group_a, group_b);
-            if is_descending {
-                comparison.reverse()
+/// 照合順序（大文字小文字無視・自然順）を考慮した比較
+fn compare_collated(a: &Value, b: &Value, key: &SortKey) -> std::cmp::Ordering {
+    match (a, b) {
+        (Value::String(s1), Value::String(s2)) if key.case_insensitive || key.natural => {
+            let (s1, s2) = if key.case_insensitive {
+                (s1.to_lowercase(), s2.to_lowercase())
             } else {
-                comparison
-            }
-        });
-    } else {
-        // フィールド指定あり：集約結果のフィールドでソート
-        sorted_data.sort_by(|a, b| {
-            let value_a = extract_aggregation_value(a, &field_name);
-            let value_b = extract_aggregation_value(b, &field_name);
-            let comparison = compare_sort_values(&value_a, &value_b);
-            if is_descending {
-                comparison.reverse()
+                (s1.clone(), s2.clone())
+            };
+
+            if key.natural {
+                compare_natural(&s1, &s2)
             } else {
-                comparison
+                s1.cmp(&s2)
+            }
+        }
+        _ => compare_sort_values(a, b),
+    }
+}
+
+/// 自然順比較（"file9" < "file10"）
+fn compare_natural(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let chunks_a = split_natural_chunks(a);
+    let chunks_b = split_natural_chunks(b);
+
+    // 先頭の0の数は、他がすべて等しい場合の最終的な比較にのみ使う
+    let mut leading_zero_tie_break = Ordering::Equal;
+
+    for (chunk_a, chunk_b) in chunks_a.iter().zip(&chunks_b) {
+        let a_is_digit = chunk_a.starts_with(|c: char| c.is_ascii_digit());
+        let b_is_digit = chunk_b.starts_with(|c: char| c.is_ascii_digit());
+
+        let comparison = if a_is_digit && b_is_digit {
+            // 先頭の0を除いて桁数→辞書順で比較
+            let digits_a = chunk_a.trim_start_matches('0');
+            let digits_b = chunk_b.trim_start_matches('0');
+            if leading_zero_tie_break == Ordering::Equal {
+                leading_zero_tie_break = chunk_a.len().cmp(&chunk_b.len());
             }
-        });
+            digits_a
+                .len()
+                .cmp(&digits_b.len())
+                .then_with(|| digits_a.cmp(digits_b))
+        } else {
+            chunk_a.cmp(chunk_b)
+        };
+
+        if comparison != Ordering::Equal {
+            return comparison;
+        }
     }
 
-    Ok(sorted_data)
+    chunks_a
+        .len()
+        .cmp(&chunks_b.len())
+        .then(leading_zero_tie_break)
 }
 
-/// ソート指定を解析（フィールド名と昇順/降順）
-fn parse_sort_spec(sort_spec: &str) -> Result<(String, bool), Error> {
-    if sort_spec.is_empty() {
-        return Ok((String::new(), false)); // デフォルトは昇順
+/// 数字部分と非数字部分に分割
+fn split_natural_chunks(text: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut previous_is_digit = None;
+
+    for (i, c) in text.char_indices() {
+        let is_digit = c.is_ascii_digit();
+        if previous_is_digit.is_some_and(|prev| prev != is_digit) {
+            chunks.push(&text[start..i]);
+            start = i;
+        }
+        previous_is_digit = Some(is_digit);
+    }
+
+    if start < text.len() {
+        chunks.push(&text[start..]);
     }
 
-    let parts: Vec<&str> = sort_spec.split(',').map(|s| s.trim()).collect();
+    chunks
+}
 
-    if parts.len() == 1 {
-        // フィールド名のみ：昇順
-        let field_name = parts[0].trim_start_matches('.').to_string();
-        Ok((field_name, false))
-    } else if parts.len() == 2 {
-        // フィールド名 + 方向指定
-        let field_name = parts[0].trim_start_matches('.').to_string();
-        let direction = parts[1].trim_matches('"').trim_matches('\'');
-        let is_descending = match direction.to_lowercase().as_str() {
-            "desc" | "descending" | "down" => true,
-            "asc" | "ascending" | "up" => false,
-            _ => {
-                return Err(Error::InvalidQuery(format!(
-                    "Invalid sort direction: '{}'. Use 'asc' or 'desc'",
-                    direction
-                )));
+/// ソート指定を解析（例: `.dept, .salary desc nulls_last` / `.name, "desc"`）
+fn parse_sort_spec(sort_spec: &str) -> Result<Vec<SortKey>, Error> {
+    if sort_spec.trim().is_empty() {
+        return Ok(vec![SortKey::new("")]); // デフォルトは値そのもので昇順
+    }
+
+    let mut sort_keys: Vec<SortKey> = Vec::new();
+
+    for part in sort_spec.split(',').map(|s| s.trim()) {
+        let mut tokens = part.split_whitespace().peekable();
+        let first = tokens.peek().copied().unwrap_or_default();
+
+        if is_sort_modifier(first) {
+            // 従来形式: sort(.field, "desc") → 直前のキーに適用
+            let key = sort_keys.last_mut().ok_or_else(|| {
+                Error::InvalidQuery(
+                    "Invalid sort specification. Use: sort(.field) or sort(.field desc, .other)"
+                        .to_string(),
+                )
+            })?;
+            for token in tokens {
+                apply_sort_modifier(key, token)?;
             }
-        };
-        Ok((field_name, is_descending))
-    } else {
-        Err(Error::InvalidQuery(
-            "Invalid sort specification. Use: sort(.field) or sort(.field, \"desc\")".to_string(),
-        ))
+        } else {
+            // フィールド（先頭の '.' は省略可）+ 修飾子
+            let mut key = SortKey::new(tokens.next().unwrap_or_default());
+            for token in tokens {
+                apply_sort_modifier(&mut key, token)?;
+            }
+            sort_keys.push(key);
+        }
     }
+
+    Ok(sort_keys)
+}
+
+/// ソート修飾子として解釈できるか
+fn is_sort_modifier(token: &str) -> bool {
+    let mut key = SortKey::new("");
+    apply_sort_modifier(&mut key, token).is_ok()
+}
+
+/// ソート修飾子を適用
+fn apply_sort_modifier(key: &mut SortKey, token: &str) -> Result<(), Error> {
+    let modifier = token.trim_matches('"').trim_matches('\'');
+    match modifier.to_lowercase().as_str() {
+        "desc" | "descending" | "down" => key.descending = true,
+        "asc" | "ascending" | "up" => key.descending = false,
+        "nulls_first" => key.nulls = NullPlacement::First,
+        "nulls_last" => key.nulls = NullPlacement::Last,
+        "ci" | "nocase" | "case_insensitive" => key.case_insensitive = true,
+        "natural" => key.natural = true,
+        _ => {
+            return Err(Error::InvalidQuery(format!(
+                "Invalid sort modifier: '{}'. Use asc, desc, nulls_first, nulls_last, ci or natural",
+                modifier
+            )));
+        }
+    }
+    Ok(())
 }
 
 /// グループ化データから集約値を抽出
@@ -881,7 +1433,9 @@ fn apply_map_operation(data: Vec<Value>, operation: &str) -> Result<Vec<Value>,
     let content = &operation[4..operation.len() - 1]; // "map(" と ")" を除去
 
     // **新機能: 複数フィールド対応**
-    if content.contains(',') && content.contains('|') {
+    // カンマ判定はフィールド部分（最初の | より前）のみ。引数内のカンマは対象外
+    let field_part = content.split('|').next().unwrap_or("");
+    if field_part.contains(',') && content.contains('|') {
         // 複数フィールドの場合: "map(.skills, .projects | join(\",\"))"
         apply_multi_field_map_operation(data, content)
     } else {
@@ -916,14 +1470,14 @@ fn apply_multi_field_map_operation(data: Vec<Value>, content: &str) -> Result<Ve
     // "(.skills, .projects | join(\",\"))" を解析
     let parts: Vec<&str> = content.split('|').map(|s| s.trim()).collect();
 
-    if parts.len() != 2 {
+    if parts.len() < 2 {
         return Err(Error::InvalidQuery(
             "Multi-field map must have format: (.field1, .field2 | operation)".to_string(),
         ));
     }
 
     let fields_part = parts[0].trim();
-    let operation = parts[1].trim();
+    let operations = &parts[1..];
 
     // フィールド部分をパース: ".skills, .projects"
     let field_paths: Vec<&str> = fields_part.split(',').map(|s| s.trim()).collect();
@@ -941,9 +1495,15 @@ fn apply_multi_field_map_operation(data: Vec<Value>, content: &str) -> Result<Ve
     let mut results = Vec::new();
 
     for item in data {
-        // 各フィールドに同じ操作を適用（ケース1）
-        let transformed_item =
-            crate::string_ops::apply_operation_to_multiple_fields(&item, &field_paths, operation)?;
+        // 各フィールドに同じ操作を順に適用（ケース1）
+        let mut transformed_item = item;
+        for operation in operations {
+            transformed_item = crate::string_ops::apply_operation_to_multiple_fields(
+                &transformed_item,
+                &field_paths,
+                operation,
+            )?;
+        }
         results.push(transformed_item);
     }
 
@@ -974,8 +1534,8 @@ fn extract_field_value(item: &Value, field_access: &str) -> Result<Value, Error>
         return Ok(item.clone());
     }
 
-    if field_access.starts_with('.') {
-        let field_name = &field_access[1..]; // '.' を除去
+    if let Some(field_name) = field_access.strip_prefix('.') {
+        // '.' を除去
 
         if let Some(value) = item.get(field_name) {
             Ok(value.clone())
@@ -1012,9 +1572,7 @@ fn update_or_create_value(
     if field_access == "." {
         // ルート値の場合は直接置き換え
         Ok(new_value)
-    } else if field_access.starts_with('.') {
-        let field_name = &field_access[1..];
-
+    } else if let Some(field_name) = field_access.strip_prefix('.') {
         // オブジェクトの場合はフィールドを更新
         if let Value::Object(mut obj) = original.clone() {
             obj.insert(field_name.to_string(), new_value);
@@ -1139,17 +1697,18 @@ fn parse_condition(condition: &str) -> Result<(String, String, String), Error> {
 
 fn evaluate_condition(item: &Value, field_path: &str, operator: &str, value: &str) -> bool {
     // フィールドパスから値を取得 (.age -> age)
-    let field_name = if field_path.starts_with('.') {
-        &field_path[1..]
-    } else {
-        field_path
-    };
+    let field_name = field_path.strip_prefix('.').unwrap_or(field_path);
 
     let field_value = match item.get(field_name) {
         Some(val) => val,
         None => return false,
     };
 
+    compare_values(field_value, operator, value)
+}
+
+/// 値と比較対象を演算子で比較
+fn compare_values(field_value: &Value, operator: &str, value: &str) -> bool {
     match operator {
         ">" => compare_greater(field_value, value),
         "<" => compare_less(field_value, value),
@@ -1170,6 +1729,9 @@ fn compare_greater(field_value: &Value, target: &str) -> bool {
                 false
             }
         }
+        // 日時文字列は時系列で比較
+        Value::String(s) => compare_datetime_strings(s, target.trim_matches('"'))
+            .is_some_and(|ordering| ordering.is_gt()),
         _ => false,
     }
 }
@@ -1183,6 +1745,9 @@ fn compare_less(field_value: &Value, target: &str) -> bool {
                 false
             }
         }
+        // 日時文字列は時系列で比較
+        Value::String(s) => compare_datetime_strings(s, target.trim_matches('"'))
+            .is_some_and(|ordering| ordering.is_lt()),
         _ => false,
     }
 }
@@ -1219,6 +1784,9 @@ fn compare_greater_equal(field_value: &Value, target: &str) -> bool {
                 false
             }
         }
+        // 日時文字列は時系列で比較
+        Value::String(s) => compare_datetime_strings(s, target.trim_matches('"'))
+            .is_some_and(|ordering| ordering.is_ge()),
         _ => false,
     }
 }
@@ -1232,6 +1800,9 @@ fn compare_less_equal(field_value: &Value, target: &str) -> bool {
                 false
             }
         }
+        // 日時文字列は時系列で比較
+        Value::String(s) => compare_datetime_strings(s, target.trim_matches('"'))
+            .is_some_and(|ordering| ordering.is_le()),
         _ => false,
     }
 }
@@ -1456,8 +2027,8 @@ pub fn parse_slice_notation_with_negative(
 
 /// 負のインデックス対応のインデックス解析
 pub fn parse_index_with_negative(index_str: &str, data_len: usize) -> Result<usize, Error> {
-    if index_str.starts_with('-') {
-        let negative_index = index_str[1..]
+    if let Some(digits) = index_str.strip_prefix('-') {
+        let negative_index = digits
             .parse::<usize>()
             .map_err(|_| Error::InvalidQuery(format!("Invalid negative index: {}", index_str)))?;
 
@@ -1622,18 +2193,17 @@ pub fn apply_sort_with_field_operation(
 ) -> Result<Vec<Value>, Error> {
     let field_path = &operation[5..operation.len() - 1]; // "sort(" と ")" を除去
 
-    let mut sorted_data = data;
-    sorted_data.sort_by(|a, b| {
-        let value_a = extract_sort_key(a, field_path);
-        let value_b = extract_sort_key(b, field_path);
+    let mut decorated: Vec<(Value, Value)> = data
+        .into_iter()
+        .map(|item| (extract_sort_key(&item, field_path), item))
+        .collect();
+    normalize_datetime_sort_keys(decorated.iter_mut().map(|(key, _)| key));
 
-        compare_sort_values(&value_a, &value_b)
-    });
+    decorated.sort_by(|(key_a, _), (key_b, _)| compare_sort_values(key_a, key_b));
 
-    Ok(sorted_data)
+    Ok(decorated.into_iter().map(|(_, item)| item).collect())
 }
 
-/// ソート用のキー値を抽出
 // pub fn extract_sort_key(item: &Value, field_path: &str) -> Value {
 //     if field_path.starts_with('.') {
 //         let field_name = &field_path[1..];
@@ -1643,18 +2213,24 @@ pub fn apply_sort_with_field_operation(
 //     }
 // }
 
-fn extract_sort_key(item: &Value, field_path: &str) -> Value {
-    if field_path == "." || field_path.is_empty() {
-        item.clone()
-    } else if field_path.starts_with('.') {
-        let field_name = &field_path[1..];
-        item.get(field_name).cloned().unwrap_or(Value::Null)
-    } else {
-        item.get(field_path).cloned().unwrap_or(Value::Null)
+/// ソート用のキー値を抽出（ネストしたパス `.a.b` に対応）
+pub(crate) fn extract_sort_key(item: &Value, field_path: &str) -> Value {
+    let path = field_path.strip_prefix('.').unwrap_or(field_path);
+    if path.is_empty() {
+        return item.clone();
+    }
+
+    let mut current = item;
+    for part in path.split('.') {
+        match current.get(part) {
+            Some(value) => current = value,
+            None => return Value::Null,
+        }
     }
+
+    current.clone()
 }
 
-/// ソート用の値比較
 // pub fn compare_sort_values(a: &Value, b: &Value) -> std::cmp::Ordering {
 //     use std::cmp::Ordering;
 //
@@ -1673,6 +2249,7 @@ fn extract_sort_key(item: &Value, field_path: &str) -> Value {
 //     }
 // }
 
+/// ソート用の値比較
 fn compare_sort_values(a: &Value, b: &Value) -> std::cmp::Ordering {
     use std::cmp::Ordering;
 
@@ -1682,6 +2259,7 @@ fn compare_sort_values(a: &Value, b: &Value) -> std::cmp::Ordering {
             let f2 = n2.as_f64().unwrap_or(0.0);
             f1.partial_cmp(&f2).unwrap_or(Ordering::Equal)
         }
+        // 日時の列は normalize_datetime_sort_keys で時系列順の文字列になっている
         (Value::String(s1), Value::String(s2)) => s1.cmp(s2),
         (Value::Bool(b1), Value::Bool(b2)) => b1.cmp(b2),
         (Value::Null, Value::Null) => Ordering::Equal,
@@ -1897,6 +2475,388 @@ mod tests {
         assert_eq!(result[2].get("score").unwrap(), &json!(92)); // Bob
     }
 
+    #[test]
+    fn test_top_and_bottom_records() {
+        let data = vec![
+            json!({"name": "A", "revenue": 300}),
+            json!({"name": "B", "revenue": 100}),
+            json!({"name": "C"}),
+            json!({"name": "D", "revenue": 500}),
+            json!({"name": "E", "revenue": 200}),
+        ];
+
+        let result = apply_pipeline_operation(data.clone(), "top(2, .revenue)").unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].get("name").unwrap(), &json!("D"));
+        assert_eq!(result[1].get("name").unwrap(), &json!("A"));
+
+        // フィールドを持たないレコードは除外される
+        let result = apply_pipeline_operation(data, "bottom(10, .revenue)").unwrap();
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[0].get("name").unwrap(), &json!("B"));
+        assert_eq!(result[3].get("name").unwrap(), &json!("D"));
+    }
+
+    #[test]
+    fn test_min_by_max_by_return_whole_record() {
+        let data = vec![
+            json!({"name": "Laptop", "price": 1200, "created_at": "2024-03-01"}),
+            json!({"name": "Mouse", "price": 25, "created_at": "2024-01-15"}),
+            json!({"name": "Phone", "price": 1200, "created_at": "2024-02-10"}),
+        ];
+
+        // 同値の場合は最初のレコード
+        let result = apply_pipeline_operation(data.clone(), "max_by(.price)").unwrap();
+        assert_eq!(result, vec![data[0].clone()]);
+
+        let result = apply_pipeline_operation(data.clone(), "min_by(.created_at)").unwrap();
+        assert_eq!(result, vec![data[1].clone()]);
+
+        assert!(apply_pipeline_operation(data, "max_by(price)").is_err());
+    }
+
+    #[test]
+    fn test_top_per_group() {
+        let data = vec![
+            json!({"category": "Books", "name": "Fiction", "sales": 20}),
+            json!({"category": "Toys", "name": "Robot", "sales": 90}),
+            json!({"category": "Books", "name": "Science", "sales": 45}),
+            json!({"category": "Toys", "name": "Ball", "sales": 10}),
+            json!({"category": "Books", "name": "History", "sales": 30}),
+        ];
+
+        let grouped = apply_pipeline_operation(data, "group_by(.category)").unwrap();
+        // グループの形（{"group": ..., "items": [...]}）を保ったまま各 items を絞る
+        let names_by_group = |result: Vec<Value>| {
+            let mut groups: Vec<(String, Vec<String>)> = result
+                .iter()
+                .map(|group| {
+                    let names = group["items"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|item| item["name"].as_str().unwrap().to_string())
+                        .collect();
+                    (group["group"].as_str().unwrap().to_string(), names)
+                })
+                .collect();
+            groups.sort();
+            groups
+        };
+
+        let result = apply_pipeline_operation(grouped.clone(), "top(2, .sales)").unwrap();
+        assert_eq!(
+            names_by_group(result),
+            vec![
+                (
+                    "Books".to_string(),
+                    vec!["Science".to_string(), "History".to_string()]
+                ),
+                (
+                    "Toys".to_string(),
+                    vec!["Robot".to_string(), "Ball".to_string()]
+                ),
+            ]
+        );
+
+        let result = apply_pipeline_operation(grouped, "min_by(.sales)").unwrap();
+        assert_eq!(
+            names_by_group(result),
+            vec![
+                ("Books".to_string(), vec!["Fiction".to_string()]),
+                ("Toys".to_string(), vec!["Ball".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explode_duplicates_parent_per_element() {
+        let data = vec![
+            json!({"id": 1, "tags": ["a", "b"], "owner": "x"}),
+            json!({"id": 2, "tags": []}),
+            json!({"id": 3}),
+        ];
+
+        let result = apply_pipeline_operation(data, "explode(.tags)").unwrap();
+        assert_eq!(
+            result,
+            vec![
+                json!({"id": 1, "tags": "a", "owner": "x"}),
+                json!({"id": 1, "tags": "b", "owner": "x"}),
+                json!({"id": 2, "tags": null}),
+                json!({"id": 3}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatten_with_depth() {
+        let data = vec![json!([1, [2, [3]]]), json!(4)];
+
+        let result = apply_pipeline_operation(data.clone(), "flatten").unwrap();
+        assert_eq!(result, vec![json!(1), json!([2, [3]]), json!(4)]);
+
+        let result = apply_pipeline_operation(data.clone(), "flatten(2)").unwrap();
+        assert_eq!(result, vec![json!(1), json!(2), json!([3]), json!(4)]);
+
+        let result = apply_pipeline_operation(data, "flatten(10)").unwrap();
+        assert_eq!(result, vec![json!(1), json!(2), json!(3), json!(4)]);
+    }
+
+    #[test]
+    fn test_unnest_merges_child_fields() {
+        let data = vec![
+            json!({"name": "Alice", "projects": [
+                {"name": "A", "hours": 10},
+                {"name": "B", "hours": 5}
+            ], "dept": "Eng"}),
+            json!({"name": "Bob", "address": {"city": "Osaka"}, "projects": []}),
+        ];
+
+        let result = apply_pipeline_operation(data.clone(), "unnest(.projects)").unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result[0],
+            json!({"name": "Alice", "projects_name": "A", "projects_hours": 10, "dept": "Eng"})
+        );
+        assert_eq!(result[1].get("projects_hours").unwrap(), &json!(5));
+        assert_eq!(
+            result[2],
+            json!({"name": "Bob", "address": {"city": "Osaka"}})
+        );
+
+        let result = apply_pipeline_operation(data.clone(), r#"unnest(.address, "")"#).unwrap();
+        assert_eq!(result[1].get("city").unwrap(), &json!("Osaka"));
+        assert!(result[1].get("address").is_none());
+
+        // 引用符内のカンマはプレフィックスの一部
+        let result = apply_pipeline_operation(data.clone(), r#"unnest(.address, "a,")"#).unwrap();
+        assert_eq!(result[1].get("a,city").unwrap(), &json!("Osaka"));
+
+        // 親の既存フィールドを上書きする場合はエラー
+        assert!(apply_pipeline_operation(data.clone(), r#"unnest(.projects, "")"#).is_err());
+        assert!(apply_pipeline_operation(data, "unnest()").is_err());
+    }
+
+    #[test]
+    fn test_distinct_by_and_duplicates_operations() {
+        let data = vec![
+            json!({"email": "a@x.com", "n": 1}),
+            json!({"email": "b@x.com", "n": 2}),
+            json!({"email": "a@x.com", "n": 3}),
+        ];
+
+        let result = apply_pipeline_operation(data.clone(), "distinct_by(.email)").unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].get("n").unwrap(), &json!(1));
+
+        let result =
+            apply_pipeline_operation(data.clone(), r#"distinct_by(.email, "last")"#).unwrap();
+        assert_eq!(result[1].get("n").unwrap(), &json!(3));
+
+        let result = apply_pipeline_operation(data.clone(), "duplicates(.email)").unwrap();
+        assert_eq!(result.len(), 2);
+
+        assert!(apply_pipeline_operation(data.clone(), "distinct_by()").is_err());
+        assert!(
+            apply_pipeline_operation(data.clone(), r#"distinct_by(.email, "middle")"#).is_err()
+        );
+        assert!(
+            apply_pipeline_operation(data.clone(), r#"distinct_by(.email, "first", "last")"#)
+                .is_err()
+        );
+        // duplicates はフィールド以外の引数を受け付けない
+        assert!(apply_pipeline_operation(data.clone(), r#"duplicates(.email, "first")"#).is_err());
+        assert!(apply_pipeline_operation(data, "duplicates(.email, extra)").is_err());
+    }
+
+    #[test]
+    fn test_distinct_by_nested_field() {
+        let data = vec![
+            json!({"user": {"id": 1}, "n": 1}),
+            json!({"user": {"id": 2}, "n": 2}),
+            json!({"user": {"id": 1}, "n": 3}),
+        ];
+
+        let result = apply_pipeline_operation(data.clone(), "distinct_by(.user.id)").unwrap();
+        let ns: Vec<i64> = result.iter().map(|v| v["n"].as_i64().unwrap()).collect();
+        assert_eq!(ns, vec![1, 2]);
+
+        let result = apply_pipeline_operation(data, "duplicates(.user.id)").unwrap();
+        let ns: Vec<i64> = result.iter().map(|v| v["n"].as_i64().unwrap()).collect();
+        assert_eq!(ns, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_multi_key_sort() {
+        let data = vec![
+            json!({"dept": "Sales", "salary": 500, "name": "A"}),
+            json!({"dept": "Eng", "salary": 700, "name": "B"}),
+            json!({"dept": "Sales", "salary": 900, "name": "C"}),
+            json!({"dept": "Eng", "salary": 700, "name": "D"}),
+            json!({"dept": "Eng", "salary": 800, "name": "E"}),
+        ];
+
+        let result = apply_pipeline_operation(data, "sort(.dept, .salary desc)").unwrap();
+        let names: Vec<&str> = result
+            .iter()
+            .map(|v| v.get("name").unwrap().as_str().unwrap())
+            .collect();
+        // 同値（B, D）は元の順序を維持
+        assert_eq!(names, vec!["E", "B", "D", "C", "A"]);
+    }
+
+    #[test]
+    fn test_sort_null_placement_and_legacy_direction() {
+        let data = vec![
+            json!({"v": 2}),
+            json!({"v": null}),
+            json!({"v": 1}),
+            json!({}),
+        ];
+
+        let result = apply_pipeline_operation(data.clone(), r#"sort(.v, "desc")"#).unwrap();
+        assert_eq!(result[0], json!({"v": 2}));
+        assert_eq!(result[1], json!({"v": 1}));
+
+        let result = apply_pipeline_operation(data.clone(), "sort(.v desc nulls_first)").unwrap();
+        assert_eq!(result[2], json!({"v": 2}));
+        assert_eq!(result[3], json!({"v": 1}));
+
+        let result = apply_pipeline_operation(data.clone(), "sort(.v nulls_last)").unwrap();
+        assert_eq!(result[0], json!({"v": 1}));
+        assert_eq!(result[1], json!({"v": 2}));
+
+        assert!(apply_pipeline_operation(data, "sort(.v sideways)").is_err());
+    }
+
+    #[test]
+    fn test_sort_bare_field_name() {
+        let data = vec![
+            json!({"department": "Sales", "n": 1}),
+            json!({"department": "Engineering", "n": 2}),
+        ];
+
+        // 先頭の '.' なしのフィールド名も従来どおり使える
+        let result = apply_pipeline_operation(data.clone(), "sort(department)").unwrap();
+        assert_eq!(result[0]["n"], json!(2));
+
+        let result = apply_pipeline_operation(data.clone(), r#"sort(department, "desc")"#).unwrap();
+        assert_eq!(result[0]["n"], json!(1));
+
+        let result = apply_pipeline_operation(data, "sort(department desc, n)").unwrap();
+        assert_eq!(result[0]["n"], json!(1));
+    }
+
+    #[test]
+    fn test_sort_collation_and_nested_paths() {
+        let data = vec![
+            json!({"file": {"name": "file10"}}),
+            json!({"file": {"name": "File2"}}),
+            json!({"file": {"name": "file9"}}),
+        ];
+
+        let result = apply_pipeline_operation(data.clone(), "sort(.file.name)").unwrap();
+        assert_eq!(result[0], json!({"file": {"name": "File2"}}));
+        assert_eq!(result[1], json!({"file": {"name": "file10"}}));
+
+        let result = apply_pipeline_operation(data, "sort(.file.name ci natural)").unwrap();
+        let names: Vec<&str> = result
+            .iter()
+            .map(|v| v["file"]["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["File2", "file9", "file10"]);
+
+        assert_eq!(compare_natural("a007b", "a7c"), std::cmp::Ordering::Less);
+        assert_eq!(compare_natural("a007c", "a7b"), std::cmp::Ordering::Greater);
+        assert_eq!(compare_natural("a007", "a7"), std::cmp::Ordering::Greater);
+        assert_eq!(compare_natural("a7b", "a007b"), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn test_sort_grouped_data_by_multiple_keys() {
+        let data = vec![
+            json!({"group": "b", "items": [], "count": 2}),
+            json!({"group": "a", "items": [], "count": 2}),
+            json!({"group": "c", "items": [], "count": 5}),
+        ];
+
+        let result = apply_pipeline_operation(data, "sort(.count desc, .group)").unwrap();
+        let groups: Vec<&str> = result
+            .iter()
+            .map(|v| v.get("group").unwrap().as_str().unwrap())
+            .collect();
+        assert_eq!(groups, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_datetime_select_and_sort() {
+        let data = vec![
+            json!({"id": 1, "ts": "2024-03-14T10:00:00+09:00"}),
+            json!({"id": 2, "ts": "2024-03-14T02:30:00Z"}),
+            json!({"id": 3, "ts": "2024-03-13 23:00:00"}),
+        ];
+
+        let result =
+            apply_pipeline_operation(data.clone(), r#"select(.ts >= "2024-03-14")"#).unwrap();
+        let ids: Vec<i64> = result.iter().map(|v| v["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        let result = apply_pipeline_operation(data, "sort(.ts)").unwrap();
+        let ids: Vec<i64> = result.iter().map(|v| v["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_sort_mixed_datetime_and_text() {
+        // 日時とそれ以外の文字列が混ざる列は、列全体を辞書順で並べる
+        let labels = [
+            "2024-03-14T10:00:00+09:00",
+            "pending",
+            "2024-03-14T02:30:00Z",
+            "14/Mar/2024:01:00:00 +0000",
+            "2024-03-13",
+            "n/a",
+        ];
+        let data: Vec<Value> = labels
+            .iter()
+            .cycle()
+            .take(60)
+            .enumerate()
+            .map(|(id, ts)| json!({"id": id, "ts": ts}))
+            .collect();
+
+        let mut expected: Vec<&str> = data.iter().map(|v| v["ts"].as_str().unwrap()).collect();
+        expected.sort();
+
+        let result = apply_pipeline_operation(data.clone(), "sort(.ts)").unwrap();
+        let sorted: Vec<&str> = result.iter().map(|v| v["ts"].as_str().unwrap()).collect();
+        assert_eq!(sorted, expected);
+
+        let result = apply_pipeline_operation(data, "top(2, .ts)").unwrap();
+        let top: Vec<&str> = result.iter().map(|v| v["ts"].as_str().unwrap()).collect();
+        assert_eq!(top, vec!["pending", "pending"]);
+    }
+
+    #[test]
+    fn test_select_with_trailing_comparison() {
+        let data = vec![
+            json!({"id": 1, "x": 10}),
+            json!({"id": 2, "x": "10"}),
+            json!({"id": 3, "x": "ten"}),
+        ];
+
+        let result =
+            apply_pipeline_operation(data.clone(), r#"select(.x | type == "number")"#).unwrap();
+        assert_eq!(result, vec![json!({"id": 1, "x": 10})]);
+
+        let result =
+            apply_pipeline_operation(data, r#"select(.x | tonumber("null") | type == "number")"#)
+                .unwrap();
+        let ids: Vec<i64> = result.iter().map(|v| v["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
     #[test]
     fn test_apply_negative_index_slice() {
         let data = vec![json!("a"), json!("b"), json!("c"), json!("d"), json!("e")];
@@ -1,25 +1,43 @@
 pub mod arg;
+#[cfg(feature = "parquet")]
+pub mod columnar;
+pub mod compression;
+pub mod datetime_ops;
 pub mod error;
 pub mod executor;
 pub mod filter;
+pub mod numeric_ops;
+pub mod object_ops;
 pub mod output;
+pub mod parse_ops;
 pub mod parser;
 pub mod setup;
+pub mod spreadsheet;
 pub mod stats_opts;
 pub mod string_ops;
 pub mod utils;
+pub mod xml_parser;
 
 pub use arg::*;
+#[cfg(feature = "parquet")]
+pub use columnar::*;
+pub use compression::*;
+pub use datetime_ops::*;
 pub use error::*;
 pub use executor::*;
 pub use filter::*;
+pub use numeric_ops::*;
+pub use object_ops::*;
 pub use output::*;
+pub use parse_ops::*;
 pub use parser::*;
 use serde_json::Value;
 pub use setup::*;
+pub use spreadsheet::*;
 pub use stats_opts::*;
 pub use string_ops::*;
 pub use utils::*;
+pub use xml_parser::*;
 
 pub fn debug_json_order(json: &Value) {
     println!("=== Original JSON field order ===");
@@ -34,12 +52,12 @@ pub fn debug_json_order(json: &Value) {
 
         // users配列の最初の要素のフィールド順序
         // Field order of the first element in the users array
-        if let Some(Value::Array(users)) = obj.get("users") {
-            if let Some(Value::Object(first_user)) = users.first() {
-                println!("First user fields:");
-                for key in first_user.keys() {
-                    println!("  {}", key);
-                }
+        if let Some(Value::Array(users)) = obj.get("users")
+            && let Some(Value::Object(first_user)) = users.first()
+        {
+            println!("First user fields:");
+            for key in first_user.keys() {
+                println!("  {}", key);
             }
         }
     }
@@ -0,0 +1,199 @@
+use serde_json::Value;
+
+use crate::{Error, value_to_string};
+
+/// 数値操作かどうかを判定
+pub fn is_numeric_operation(operation: &str) -> bool {
+    let name = operation
+        .split_once('(')
+        .map(|(name, _)| name)
+        .unwrap_or(operation);
+
+    let has_valid_args = !operation.contains('(') || operation.ends_with(')');
+
+    has_valid_args
+        && matches!(
+            name,
+            "round" | "floor" | "ceil" | "abs" | "sqrt" | "ln" | "log10" | "pow" | "clamp"
+        )
+}
+
+/// 数値操作を適用（null はそのまま返す）
+pub fn apply_numeric_operation(value: &Value, operation: &str) -> Result<Value, Error> {
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+
+    let (name, args) = parse_numeric_arguments(operation)?;
+    let number = extract_number(value, name)?;
+
+    let result = match (name, args.as_slice()) {
+        ("round", []) => number.round(),
+        ("round", [digits]) => round_to(number, *digits)?,
+        ("floor", []) => number.floor(),
+        ("ceil", []) => number.ceil(),
+        ("abs", []) => number.abs(),
+        ("sqrt", []) => number.sqrt(),
+        ("ln", []) => number.ln(),
+        ("log10", []) => number.log10(),
+        ("pow", [exponent]) => number.powf(*exponent),
+        ("clamp", [min, max]) if min <= max => number.clamp(*min, *max),
+        ("clamp", [_, _]) => {
+            return Err(Error::NumericOperation(
+                "clamp: min must be less than or equal to max".to_string(),
+            ));
+        }
+        _ => {
+            return Err(Error::NumericOperation(format!(
+                "Invalid arguments for {}: {}",
+                name, operation
+            )));
+        }
+    };
+
+    number_to_value(result, name)
+}
+
+/// 小数点以下 digits 桁で四捨五入
+fn round_to(number: f64, digits: f64) -> Result<f64, Error> {
+    if digits < 0.0 || digits.fract() != 0.0 {
+        return Err(Error::NumericOperation(format!(
+            "round: digits must be a non-negative integer, got: {}",
+            digits
+        )));
+    }
+
+    let factor = 10f64.powi(digits as i32);
+    Ok((number * factor).round() / factor)
+}
+
+/// 数値（または数値文字列）を取り出す
+fn extract_number(value: &Value, operation: &str) -> Result<f64, Error> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        Error::NumericOperation(format!(
+            "{} can only be applied to numeric values, got: {}",
+            operation,
+            value_to_string(value)
+        ))
+    })
+}
+
+/// 結果を JSON の数値に変換（整数になる場合は整数で返す）
+fn number_to_value(number: f64, operation: &str) -> Result<Value, Error> {
+    if !number.is_finite() {
+        return Err(Error::NumericOperation(format!(
+            "{} produced a non-finite result",
+            operation
+        )));
+    }
+
+    if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+        return Ok(Value::Number((number as i64).into()));
+    }
+
+    Ok(serde_json::Number::from_f64(number)
+        .map(Value::Number)
+        .unwrap_or(Value::Null))
+}
+
+/// "clamp(0, 100)" → ("clamp", [0.0, 100.0])
+fn parse_numeric_arguments(operation: &str) -> Result<(&str, Vec<f64>), Error> {
+    let Some((name, rest)) = operation.split_once('(') else {
+        return Ok((operation.trim(), Vec::new()));
+    };
+
+    let inner = rest
+        .strip_suffix(')')
+        .ok_or_else(|| Error::NumericOperation("Missing closing parenthesis".to_string()))?;
+
+    if inner.trim().is_empty() {
+        return Ok((name.trim(), Vec::new()));
+    }
+
+    let args = inner
+        .split(',')
+        .map(|arg| {
+            arg.trim().parse::<f64>().map_err(|_| {
+                Error::NumericOperation(format!(
+                    "{}: argument must be a number, got: {}",
+                    name.trim(),
+                    arg.trim()
+                ))
+            })
+        })
+        .collect::<Result<Vec<f64>, Error>>()?;
+
+    Ok((name.trim(), args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rounding_operations() {
+        let value = json!(12.3456);
+
+        assert_eq!(apply_numeric_operation(&value, "round").unwrap(), json!(12));
+        assert_eq!(
+            apply_numeric_operation(&value, "round(2)").unwrap(),
+            json!(12.35)
+        );
+        assert_eq!(apply_numeric_operation(&value, "floor").unwrap(), json!(12));
+        assert_eq!(apply_numeric_operation(&value, "ceil").unwrap(), json!(13));
+        assert_eq!(
+            apply_numeric_operation(&json!(-2.5), "abs").unwrap(),
+            json!(2.5)
+        );
+        assert!(apply_numeric_operation(&value, "round(-1)").is_err());
+    }
+
+    #[test]
+    fn test_math_operations() {
+        assert_eq!(
+            apply_numeric_operation(&json!(16), "sqrt").unwrap(),
+            json!(4)
+        );
+        assert_eq!(
+            apply_numeric_operation(&json!(1000), "log10").unwrap(),
+            json!(3)
+        );
+        assert_eq!(apply_numeric_operation(&json!(1), "ln").unwrap(), json!(0));
+        assert_eq!(
+            apply_numeric_operation(&json!(2), "pow(10)").unwrap(),
+            json!(1024)
+        );
+        assert!(apply_numeric_operation(&json!(-1), "sqrt").is_err());
+        assert!(apply_numeric_operation(&json!(0), "ln").is_err());
+    }
+
+    #[test]
+    fn test_clamp_and_input_types() {
+        assert_eq!(
+            apply_numeric_operation(&json!(150), "clamp(0, 100)").unwrap(),
+            json!(100)
+        );
+        assert_eq!(
+            apply_numeric_operation(&json!(-5.5), "clamp(0, 100)").unwrap(),
+            json!(0)
+        );
+        assert!(apply_numeric_operation(&json!(5), "clamp(10, 0)").is_err());
+
+        // 数値文字列と null
+        assert_eq!(
+            apply_numeric_operation(&json!("2.675"), "round(1)").unwrap(),
+            json!(2.7)
+        );
+        assert_eq!(
+            apply_numeric_operation(&Value::Null, "round").unwrap(),
+            Value::Null
+        );
+        assert!(apply_numeric_operation(&json!("abc"), "abs").is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use serde_json::{Map, Value};
+
+use crate::{Error, apply_pipeline_operation, value_to_string};
+
+/// オブジェクト操作を各要素に適用する
+pub fn apply_object_operation(data: Vec<Value>, operation: &str) -> Result<Vec<Value>, Error> {
+    data.into_iter()
+        .map(|item| apply_object_operation_to_value(item, operation))
+        .collect()
+}
+
+/// オブジェクト操作かどうかを判定
+pub fn is_object_operation(operation: &str) -> bool {
+    matches!(operation, "keys" | "values" | "to_entries" | "from_entries")
+        || (operation.starts_with("with_entries(") && operation.ends_with(')'))
+}
+
+/// 単一の値にオブジェクト操作を適用
+fn apply_object_operation_to_value(value: Value, operation: &str) -> Result<Value, Error> {
+    match operation {
+        "keys" => keys(&value),
+        "values" => values(value),
+        "to_entries" => Ok(Value::Array(to_entries(value, "to_entries")?)),
+        "from_entries" => from_entries(value),
+        op if op.starts_with("with_entries(") && op.ends_with(')') => {
+            let inner = op[13..op.len() - 1].trim();
+            with_entries(value, inner)
+        }
+        _ => Err(Error::InvalidQuery(format!(
+            "Unknown object operation: {}",
+            operation
+        ))),
+    }
+}
+
+/// キー一覧（配列の場合はインデックス）
+fn keys(value: &Value) -> Result<Value, Error> {
+    match value {
+        Value::Object(obj) => Ok(Value::Array(
+            obj.keys().map(|k| Value::String(k.clone())).collect(),
+        )),
+        Value::Array(arr) => Ok(Value::Array(
+            (0..arr.len()).map(|i| Value::Number(i.into())).collect(),
+        )),
+        _ => Err(type_error("keys", "objects or arrays", value)),
+    }
+}
+
+/// 値一覧
+fn values(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::Object(obj) => Ok(Value::Array(obj.into_iter().map(|(_, v)| v).collect())),
+        Value::Array(_) => Ok(value),
+        _ => Err(type_error("values", "objects or arrays", &value)),
+    }
+}
+
+/// オブジェクトを [{key, value}] に変換
+fn to_entries(value: Value, operation: &str) -> Result<Vec<Value>, Error> {
+    match value {
+        Value::Object(obj) => Ok(obj
+            .into_iter()
+            .map(|(key, value)| {
+                let mut entry = Map::new();
+                entry.insert("key".to_string(), Value::String(key));
+                entry.insert("value".to_string(), value);
+                Value::Object(entry)
+            })
+            .collect()),
+        _ => Err(type_error(operation, "objects", &value)),
+    }
+}
+
+/// [{key, value}] をオブジェクトに戻す（k/v, name/value も受け付ける）
+fn from_entries(value: Value) -> Result<Value, Error> {
+    let Value::Array(entries) = value else {
+        return Err(type_error("from_entries", "arrays of entries", &value));
+    };
+
+    let mut obj = Map::new();
+    for entry in entries {
+        // null のキーは無視して次の候補を使う
+        let key = ["key", "k", "name"]
+            .iter()
+            .filter_map(|name| entry.get(*name))
+            .find(|key| !key.is_null())
+            .ok_or_else(|| {
+                Error::InvalidQuery(format!("from_entries: entry has no key: {}", entry))
+            })?;
+        let value = ["value", "v"]
+            .iter()
+            .find_map(|name| entry.get(*name))
+            .cloned()
+            .unwrap_or(Value::Null);
+
+        obj.insert(value_to_string(key), value);
+    }
+
+    Ok(Value::Object(obj))
+}
+
+/// to_entries → 変換 → from_entries
+fn with_entries(value: Value, operation: &str) -> Result<Value, Error> {
+    let entries = to_entries(value, "with_entries")?;
+
+    // select(...) や map(...) はそのまま、それ以外は map の中身として扱う
+    let transformed = if operation.starts_with("select(") || operation.starts_with("map(") {
+        apply_pipeline_operation(entries, operation)?
+    } else {
+        apply_pipeline_operation(entries, &format!("map({})", operation))?
+    };
+
+    from_entries(Value::Array(transformed))
+}
+
+fn type_error(operation: &str, expected: &str, value: &Value) -> Error {
+    let type_name = match value {
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+        Value::Null => "null",
+    };
+    Error::InvalidQuery(format!(
+        "{} can only be applied to {}, got: {}",
+        operation, expected, type_name
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_keys_and_values() {
+        let data = vec![json!({"app": "web", "tier": "frontend"})];
+
+        let result = apply_object_operation(data.clone(), "keys").unwrap();
+        assert_eq!(result, vec![json!(["app", "tier"])]);
+
+        let result = apply_object_operation(data, "values").unwrap();
+        assert_eq!(result, vec![json!(["web", "frontend"])]);
+
+        let result = apply_object_operation(vec![json!("text")], "keys");
+        assert!(
+            matches!(result, Err(Error::InvalidQuery(msg)) if msg.contains("objects or arrays"))
+        );
+    }
+
+    #[test]
+    fn test_entries_round_trip() {
+        let data = vec![json!({"a": 1, "b": null})];
+
+        let entries = apply_object_operation(data.clone(), "to_entries").unwrap();
+        assert_eq!(
+            entries,
+            vec![json!([{"key": "a", "value": 1}, {"key": "b", "value": null}])]
+        );
+
+        let restored = apply_object_operation(entries, "from_entries").unwrap();
+        assert_eq!(restored, data);
+
+        let result =
+            apply_object_operation(vec![json!([{"name": "x", "v": 2}])], "from_entries").unwrap();
+        assert_eq!(result, vec![json!({"x": 2})]);
+
+        // "key" が null の場合は "name" / "k" を使う
+        let result = apply_object_operation(
+            vec![json!([{"key": null, "name": "x", "value": 1}])],
+            "from_entries",
+        )
+        .unwrap();
+        assert_eq!(result, vec![json!({"x": 1})]);
+    }
+
+    #[test]
+    fn test_with_entries() {
+        let data = vec![json!({"app": "web", "env": "prod", "replicas": 3})];
+
+        let result = apply_object_operation(data.clone(), "with_entries(.key | upper)").unwrap();
+        assert_eq!(
+            result,
+            vec![json!({"APP": "web", "ENV": "prod", "REPLICAS": 3})]
+        );
+
+        let result =
+            apply_object_operation(data, r#"with_entries(select(.key == "env"))"#).unwrap();
+        assert_eq!(result, vec![json!({"env": "prod"})]);
+    }
+}
@@ -2,7 +2,10 @@ use indexmap::IndexSet;
 use serde_json::Value;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
-use crate::{Error, OutputFormat, value_to_string};
+use crate::{
+    DEFAULT_JSON_INDENT, Error, OutputFormat, YAML_DOCUMENT_INDEX_KEY, display_width,
+    pad_display_width, value_to_string,
+};
 
 #[derive(Debug)]
 enum DataType {
@@ -73,9 +76,12 @@ pub fn format_output(data: &[Value], format: OutputFormat) -> Result<(), Error>
     let use_colors = should_use_colors();
 
     match format {
-        OutputFormat::Json => {
+        OutputFormat::Json { indent, raw } => {
             // 明示的にJSON出力
-            print_as_json(data, use_colors)?;
+            print_as_json(data, indent, raw, use_colors)?;
+        }
+        OutputFormat::JsonLines { raw } => {
+            print_as_json_lines(data, raw, use_colors)?;
         }
         OutputFormat::Table => {
             // 明示的にテーブル出力（可能な場合）
@@ -99,6 +105,19 @@ pub fn format_output(data: &[Value], format: OutputFormat) -> Result<(), Error>
         OutputFormat::Csv => {
             print_as_csv(data, use_colors)?;
         }
+        OutputFormat::Toml => {
+            print_as_toml(data)?;
+        }
+        OutputFormat::Yaml {
+            documents,
+            indexed_documents,
+        } => {
+            print!("{}", format_yaml(data, documents, indexed_documents)?);
+        }
+        #[cfg(feature = "parquet")]
+        OutputFormat::Parquet => {
+            write_parquet_to_stdout(data)?;
+        }
         OutputFormat::Auto => {
             // 既存のスマート判定ロジック
             match analyze_data_structure(data) {
@@ -111,10 +130,12 @@ pub fn format_output(data: &[Value], format: OutputFormat) -> Result<(), Error>
                     } else if is_simple_values(&flattened) {
                         print_as_list(&flattened, use_colors)?;
                     } else {
-                        print_as_json(data, use_colors)?;
+                        print_as_json(data, Some(DEFAULT_JSON_INDENT), false, use_colors)?;
                     }
                 }
-                DataType::Mixed => print_as_json(data, use_colors)?,
+                DataType::Mixed => {
+                    print_as_json(data, Some(DEFAULT_JSON_INDENT), false, use_colors)?
+                }
             }
         }
     }
@@ -272,24 +293,218 @@ fn print_as_list(data: &[Value], use_colors: bool) -> Result<(), Error> {
     Ok(())
 }
 
-fn print_as_json(data: &[Value], use_colors: bool) -> Result<(), Error> {
-    if use_colors {
-        print_colored_json_simple(data)?;
-    } else {
-        let json = serde_json::to_string_pretty(data).map_err(Error::Json)?;
-        println!("{}", json);
+fn print_as_json(
+    data: &[Value],
+    indent: Option<usize>,
+    raw: bool,
+    use_colors: bool,
+) -> Result<(), Error> {
+    if !raw {
+        return print_json(data, indent, use_colors);
+    }
+
+    // raw の場合は結果ごとに出力し、文字列は引用符も色も付けない
+    for value in data {
+        match value {
+            Value::String(s) => println!("{}", s),
+            _ => print_json(value, indent, use_colors)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// JSON Lines として1行に1つの値を出力
+fn print_as_json_lines(data: &[Value], raw: bool, use_colors: bool) -> Result<(), Error> {
+    for value in data {
+        match value {
+            Value::String(s) if raw => println!("{}", s),
+            _ => print_json(value, None, use_colors)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// 値を JSON として出力（indent が None なら1行）
+fn print_json<T: serde::Serialize + ?Sized>(
+    value: &T,
+    indent: Option<usize>,
+    use_colors: bool,
+) -> Result<(), Error> {
+    let json = to_json_string(value, indent)?;
+
+    match (use_colors, indent) {
+        (false, _) => println!("{}", json),
+        (true, Some(_)) => print_colored_json_simple(&json)?,
+        (true, None) => print_colored_compact_json(&json)?,
     }
 
     Ok(())
 }
 
-fn print_colored_json_simple(data: &[Value]) -> Result<(), Error> {
+fn to_json_string<T: serde::Serialize + ?Sized>(
+    value: &T,
+    indent: Option<usize>,
+) -> Result<String, Error> {
+    let Some(indent) = indent else {
+        return Ok(serde_json::to_string(value)?);
+    };
+
+    let indent = " ".repeat(indent);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut buffer = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    value.serialize(&mut serializer)?;
+
+    // serde_json は常に UTF-8 を出力する
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+fn print_as_toml(data: &[Value]) -> Result<(), Error> {
+    // TOML には null がないため、null のフィールドと配列要素は除外して警告する
+    let mut dropped = Vec::new();
+
+    // TOML のトップレベルはテーブルのみなので、単一オブジェクト以外は items 配下に出力
+    let document = match data {
+        [value @ Value::Object(_)] => strip_nulls(value, "", &mut dropped),
+        _ => {
+            let items = Value::Array(data.to_vec());
+            let mut table = serde_json::Map::new();
+            table.insert(
+                "items".to_string(),
+                strip_nulls(&items, "items", &mut dropped),
+            );
+            Value::Object(table)
+        }
+    };
+
+    print!("{}", toml::to_string_pretty(&document)?);
+    report_dropped_nulls(&dropped);
+
+    Ok(())
+}
+
+/// 警告に表示する除外した null の位置の最大数
+const MAX_REPORTED_NULL_PATHS: usize = 5;
+
+fn report_dropped_nulls(paths: &[String]) {
+    if paths.is_empty() {
+        return;
+    }
+
+    let mut shown = paths
+        .iter()
+        .take(MAX_REPORTED_NULL_PATHS)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if paths.len() > MAX_REPORTED_NULL_PATHS {
+        shown.push_str(", ...");
+    }
+
+    eprintln!(
+        "Warning: TOML has no null; dropped {} null value(s): {}",
+        paths.len(),
+        shown
+    );
+}
+
+/// 結果を YAML に変換（キーの順序を保ち、ネストはブロック形式）
+///
+/// `documents` が true の場合は結果ごとに `---` で区切ったドキュメントとして出力する。
+/// `indexed_documents`（入力が複数ドキュメントの YAML で `document_index` を追加した）
+/// の場合、全ての結果がドキュメント全体なら同じくドキュメントごとに書き戻し、
+/// 追加した `document_index` は取り除く。それ以外は1つのドキュメント。
+fn format_yaml(data: &[Value], documents: bool, indexed_documents: bool) -> Result<String, Error> {
+    let whole_documents = indexed_documents
+        && data.iter().all(|value| {
+            value
+                .as_object()
+                .is_some_and(|map| map.contains_key(YAML_DOCUMENT_INDEX_KEY))
+        });
+
+    if !documents && !whole_documents {
+        return Ok(match data {
+            [value] => serde_yaml::to_string(value)?,
+            _ => serde_yaml::to_string(data)?,
+        });
+    }
+
+    let mut output = String::new();
+    for (i, value) in data.iter().enumerate() {
+        let mut document = value.clone();
+        // 読み込み時に追加した位置は書き戻さない
+        if indexed_documents && let Value::Object(map) = &mut document {
+            map.shift_remove(YAML_DOCUMENT_INDEX_KEY);
+        }
+
+        if i > 0 {
+            output.push_str("---\n");
+        }
+        output.push_str(&serde_yaml::to_string(&document)?);
+    }
+
+    Ok(output)
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet_to_stdout(data: &[Value]) -> Result<(), Error> {
+    let stdout = std::io::stdout();
+
+    // バイナリを端末に出力しない
+    if std::io::IsTerminal::is_terminal(&stdout) {
+        return Err(Error::InvalidFormat(
+            "refusing to write Parquet to a terminal; redirect the output to a file".to_string(),
+        ));
+    }
+
+    crate::write_parquet(data, std::io::BufWriter::new(stdout))
+}
+
+/// TOML には null がないため、null の値を取り除く
+/// null のフィールドと配列要素を除外（除外した位置を dropped に追加）
+fn strip_nulls(value: &Value, path: &str, dropped: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter_map(|(key, v)| {
+                    let child_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    if v.is_null() {
+                        dropped.push(child_path);
+                        return None;
+                    }
+                    Some((key.clone(), strip_nulls(v, &child_path, dropped)))
+                })
+                .collect(),
+        ),
+        Value::Array(array) => Value::Array(
+            array
+                .iter()
+                .enumerate()
+                .filter_map(|(i, v)| {
+                    let child_path = format!("{}[{}]", path, i);
+                    if v.is_null() {
+                        dropped.push(child_path);
+                        return None;
+                    }
+                    Some(strip_nulls(v, &child_path, dropped))
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+fn print_colored_json_simple(json: &str) -> Result<(), Error> {
     let mut stdout = StandardStream::stdout(ColorChoice::Always);
     let colors = ColorScheme::new();
 
     // シンプルなアプローチ：行ごとに処理
-    let json = serde_json::to_string_pretty(data).map_err(Error::Json)?;
-
     for line in json.lines() {
         let trimmed = line.trim();
 
@@ -341,6 +556,62 @@ fn print_colored_json_simple(data: &[Value]) -> Result<(), Error> {
     Ok(())
 }
 
+/// 1行の JSON を色付きで出力（キーと構造文字は青、値は型に応じた色）
+fn print_colored_compact_json(json: &str) -> Result<(), Error> {
+    let mut stdout = StandardStream::stdout(ColorChoice::Always);
+    let colors = ColorScheme::new();
+
+    // 空白を含まない JSON をトークンごとに処理
+    let mut rest = json;
+    while let Some(first) = rest.chars().next() {
+        let token_length = match first {
+            '"' => string_token_length(rest),
+            '{' | '}' | '[' | ']' | ',' | ':' => 1,
+            _ => rest.find([',', '}', ']']).unwrap_or(rest.len()),
+        };
+        let (token, tail) = rest.split_at(token_length);
+
+        let color = match first {
+            '"' if tail.starts_with(':') => Some(&colors.header),
+            '"' => Some(&colors.string),
+            '{' | '}' | '[' | ']' => Some(&colors.header),
+            ',' | ':' => None,
+            't' | 'f' => Some(&colors.boolean),
+            'n' => Some(&colors.null),
+            _ => Some(&colors.number),
+        };
+
+        match color {
+            Some(color) => {
+                stdout.set_color(color)?;
+                print!("{}", token);
+                stdout.reset()?;
+            }
+            None => print!("{}", token),
+        }
+
+        rest = tail;
+    }
+    println!();
+
+    Ok(())
+}
+
+/// 先頭の文字列トークン（引用符を含む）のバイト長
+fn string_token_length(json: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in json.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return i + 1,
+            _ => {}
+        }
+    }
+
+    json.len()
+}
+
 fn print_colored_json_value(
     value_str: &str,
     colors: &ColorScheme,
@@ -522,14 +793,14 @@ fn print_as_table(data: &[Value], use_colors: bool) -> Result<(), Error> {
 
     // ヘッダーの幅
     for (i, field) in fields.iter().enumerate() {
-        max_widths[i] = field.len();
+        max_widths[i] = display_width(field);
     }
 
     // データの幅
     for item in data {
         for (i, field) in fields.iter().enumerate() {
             let value_str = get_flattened_value(item, field);
-            max_widths[i] = max_widths[i].max(value_str.len());
+            max_widths[i] = max_widths[i].max(display_width(&value_str));
         }
     }
 
@@ -553,7 +824,7 @@ fn print_colored_table(
     // 3. ヘッダー出力（色付き）
     stdout.set_color(&colors.header)?;
     for (i, field) in fields.iter().enumerate() {
-        print!("{:<width$}", field, width = max_widths[i]);
+        print!("{}", pad_display_width(field, max_widths[i]));
         if i < fields.len() - 1 {
             print!("  ");
         }
@@ -571,7 +842,7 @@ fn print_colored_table(
             let color = get_color_for_value(&value, &colors);
 
             stdout.set_color(color)?;
-            print!("{:<width$}", value_str, width = max_widths[i]);
+            print!("{}", pad_display_width(&value_str, max_widths[i]));
             stdout.reset()?;
 
             if i < fields.len() - 1 {
@@ -587,7 +858,7 @@ fn print_colored_table(
 fn print_plain_table(data: &[Value], fields: &[String], max_widths: &[usize]) {
     // 3. ヘッダー出力
     for (i, field) in fields.iter().enumerate() {
-        print!("{:<width$}", field, width = max_widths[i]);
+        print!("{}", pad_display_width(field, max_widths[i]));
         if i < fields.len() - 1 {
             print!("  ");
         }
@@ -598,7 +869,7 @@ fn print_plain_table(data: &[Value], fields: &[String], max_widths: &[usize]) {
     for item in data {
         for (i, field) in fields.iter().enumerate() {
             let value_str = get_flattened_value(item, field);
-            print!("{:<width$}", value_str, width = max_widths[i]);
+            print!("{}", pad_display_width(&value_str, max_widths[i]));
             if i < fields.len() - 1 {
                 print!("  ");
             }
@@ -652,7 +923,7 @@ fn print_colored_data_info(data: &[Value]) -> Result<(), Error> {
 
                     // フィールド名を色付き
                     stdout.set_color(&colors.string)?;
-                    print!("  {:<15}", key);
+                    print!("  {}", pad_display_width(key, 15));
                     stdout.reset()?;
 
                     // 型情報を色付き
@@ -673,18 +944,18 @@ fn print_colored_data_info(data: &[Value]) -> Result<(), Error> {
                 for (key, value) in obj {
                     if let Value::Array(arr) = value {
                         stdout.set_color(&colors.array_info)?;
-                        print!("  {:<15}", key);
+                        print!("  {}", pad_display_width(key, 15));
                         stdout.reset()?;
                         println!(" [{} items]", arr.len());
 
-                        if let Some(first_elem) = arr.first() {
-                            if let Value::Object(elem_obj) = first_elem {
-                                print!("    └─ ");
-                                let sub_fields: Vec<&String> = elem_obj.keys().collect();
-                                let sub_fields: Vec<&str> =
-                                    sub_fields.into_iter().map(|f| f.as_str()).collect();
-                                println!("{}", sub_fields.join(", "));
-                            }
+                        if let Some(first_elem) = arr.first()
+                            && let Value::Object(elem_obj) = first_elem
+                        {
+                            print!("    └─ ");
+                            let sub_fields: Vec<&String> = elem_obj.keys().collect();
+                            let sub_fields: Vec<&str> =
+                                sub_fields.into_iter().map(|f| f.as_str()).collect();
+                            println!("{}", sub_fields.join(", "));
                         }
                     }
                 }
@@ -716,7 +987,7 @@ fn print_colored_data_info(data: &[Value]) -> Result<(), Error> {
                     let sample_value = get_sample_value(value);
 
                     stdout.set_color(&colors.string)?;
-                    print!("  {:<15}", key);
+                    print!("  {}", pad_display_width(key, 15));
                     stdout.reset()?;
 
                     let type_color = get_color_for_value(value, &colors);
@@ -735,18 +1006,18 @@ fn print_colored_data_info(data: &[Value]) -> Result<(), Error> {
                 for (key, value) in obj {
                     if let Value::Array(arr) = value {
                         stdout.set_color(&colors.array_info)?;
-                        print!("  {:<15}", key);
+                        print!("  {}", pad_display_width(key, 15));
                         stdout.reset()?;
                         println!(" [{} items]", arr.len());
 
-                        if let Some(first_elem) = arr.first() {
-                            if let Value::Object(elem_obj) = first_elem {
-                                print!("    └─ ");
-                                let sub_fields: Vec<&String> = elem_obj.keys().collect();
-                                let sub_fields: Vec<&str> =
-                                    sub_fields.into_iter().map(|f| f.as_str()).collect();
-                                println!("{}", sub_fields.join(", "));
-                            }
+                        if let Some(first_elem) = arr.first()
+                            && let Value::Object(elem_obj) = first_elem
+                        {
+                            print!("    └─ ");
+                            let sub_fields: Vec<&String> = elem_obj.keys().collect();
+                            let sub_fields: Vec<&str> =
+                                sub_fields.into_iter().map(|f| f.as_str()).collect();
+                            println!("{}", sub_fields.join(", "));
                         }
                     }
                 }
@@ -781,22 +1052,27 @@ fn print_plain_data_info(data: &[Value]) {
                 for (key, value) in obj {
                     let field_type = get_value_type_info(value);
                     let sample_value = get_sample_value(value);
-                    println!("  {:<15} {:<10} (e.g., {})", key, field_type, sample_value);
+                    println!(
+                        "  {} {:<10} (e.g., {})",
+                        pad_display_width(key, 15),
+                        field_type,
+                        sample_value
+                    );
                 }
 
                 println!();
                 println!("Array Fields:");
                 for (key, value) in obj {
                     if let Value::Array(arr) = value {
-                        println!("  {:<15} [{} items]", key, arr.len());
-                        if let Some(first_elem) = arr.first() {
-                            if let Value::Object(elem_obj) = first_elem {
-                                print!("    └─ ");
-                                let sub_fields: Vec<&String> = elem_obj.keys().collect();
-                                let sub_fields: Vec<&str> =
-                                    sub_fields.into_iter().map(|f| f.as_str()).collect();
-                                println!("{}", sub_fields.join(", "));
-                            }
+                        println!("  {} [{} items]", pad_display_width(key, 15), arr.len());
+                        if let Some(first_elem) = arr.first()
+                            && let Value::Object(elem_obj) = first_elem
+                        {
+                            print!("    └─ ");
+                            let sub_fields: Vec<&String> = elem_obj.keys().collect();
+                            let sub_fields: Vec<&str> =
+                                sub_fields.into_iter().map(|f| f.as_str()).collect();
+                            println!("{}", sub_fields.join(", "));
                         }
                     }
                 }
@@ -822,22 +1098,27 @@ fn print_plain_data_info(data: &[Value]) {
                 for (key, value) in obj {
                     let field_type = get_value_type_info(value);
                     let sample_value = get_sample_value(value);
-                    println!("  {:<15} {:<10} (e.g., {})", key, field_type, sample_value);
+                    println!(
+                        "  {} {:<10} (e.g., {})",
+                        pad_display_width(key, 15),
+                        field_type,
+                        sample_value
+                    );
                 }
 
                 println!();
                 println!("Array Fields:");
                 for (key, value) in obj {
                     if let Value::Array(arr) = value {
-                        println!("  {:<15} [{} items]", key, arr.len());
-                        if let Some(first_elem) = arr.first() {
-                            if let Value::Object(elem_obj) = first_elem {
-                                print!("    └─ ");
-                                let sub_fields: Vec<&String> = elem_obj.keys().collect();
-                                let sub_fields: Vec<&str> =
-                                    sub_fields.into_iter().map(|f| f.as_str()).collect();
-                                println!("{}", sub_fields.join(", "));
-                            }
+                        println!("  {} [{} items]", pad_display_width(key, 15), arr.len());
+                        if let Some(first_elem) = arr.first()
+                            && let Value::Object(elem_obj) = first_elem
+                        {
+                            print!("    └─ ");
+                            let sub_fields: Vec<&String> = elem_obj.keys().collect();
+                            let sub_fields: Vec<&str> =
+                                sub_fields.into_iter().map(|f| f.as_str()).collect();
+                            println!("{}", sub_fields.join(", "));
                         }
                     }
                 }
@@ -851,3 +1132,87 @@ fn print_plain_data_info(data: &[Value]) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_strip_nulls_reports_paths() {
+        let mut dropped = Vec::new();
+        let value = json!({"items": [{"id": 1, "email": null, "tags": ["a", null]}, null]});
+
+        assert_eq!(
+            strip_nulls(&value, "", &mut dropped),
+            json!({"items": [{"id": 1, "tags": ["a"]}]})
+        );
+        assert_eq!(
+            dropped,
+            vec!["items[0].email", "items[0].tags[1]", "items[1]"]
+        );
+    }
+
+    #[test]
+    fn test_yaml_output() {
+        let data =
+            vec![json!({"name": "web", "ports": [80, 443], "env": {"zone": "a", "debug": false}})];
+        assert_eq!(
+            format_yaml(&data, false, false).unwrap(),
+            "name: web\nports:\n- 80\n- 443\nenv:\n  zone: a\n  debug: false\n"
+        );
+
+        let data = vec![json!({"id": 1}), json!("two")];
+        assert_eq!(
+            format_yaml(&data, false, false).unwrap(),
+            "- id: 1\n- two\n"
+        );
+        assert_eq!(
+            format_yaml(&data, true, false).unwrap(),
+            "id: 1\n---\ntwo\n"
+        );
+    }
+
+    #[test]
+    fn test_json_string_indent() {
+        let data = [json!({"a": [1, "x"]})];
+        assert_eq!(
+            to_json_string(&data[..], None).unwrap(),
+            r#"[{"a":[1,"x"]}]"#
+        );
+        assert_eq!(
+            to_json_string(&data[0], Some(4)).unwrap(),
+            "{\n    \"a\": [\n        1,\n        \"x\"\n    ]\n}"
+        );
+    }
+
+    #[test]
+    fn test_string_token_length() {
+        assert_eq!(string_token_length(r#""key":1"#), 5);
+        assert_eq!(string_token_length(r#""a\"b",1"#), 6);
+        assert_eq!(string_token_length(r#""a\\",1"#), 5);
+    }
+
+    #[test]
+    fn test_yaml_output_document_stream() {
+        // 複数ドキュメントの YAML から読んだ結果は document_index を除いて書き戻す
+        let data = vec![
+            json!({"kind": "Service", "document_index": 0}),
+            json!({"kind": "Deployment", "document_index": 2}),
+        ];
+        assert_eq!(
+            format_yaml(&data, false, true).unwrap(),
+            "kind: Service\n---\nkind: Deployment\n"
+        );
+
+        // 入力由来の document_index フィールドはそのまま出力する
+        assert_eq!(
+            format_yaml(&data, false, false).unwrap(),
+            "- kind: Service\n  document_index: 0\n- kind: Deployment\n  document_index: 2\n"
+        );
+        assert_eq!(
+            format_yaml(&data, true, false).unwrap(),
+            "kind: Service\ndocument_index: 0\n---\nkind: Deployment\ndocument_index: 2\n"
+        );
+    }
+}
@@ -0,0 +1,740 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use chrono::SecondsFormat;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::setup::infer_value_type;
+use crate::{Error, parse_datetime, parse_operation_arguments};
+
+/// 名前付きパターンの展開の最大深さ（循環参照対策）
+const MAX_PATTERN_DEPTH: usize = 10;
+
+/// 組み込みのgrokパターン
+const GROK_PATTERNS: &[(&str, &str)] = &[
+    // 基本
+    ("WORD", r"\b\w+\b"),
+    ("NOTSPACE", r"\S+"),
+    ("SPACE", r"\s*"),
+    ("DATA", r".*?"),
+    ("GREEDYDATA", r".*"),
+    ("QUOTEDSTRING", r#""(?:[^"\\]|\\.)*""#),
+    ("QS", r"%{QUOTEDSTRING}"),
+    (
+        "UUID",
+        r"[A-Fa-f0-9]{8}-(?:[A-Fa-f0-9]{4}-){3}[A-Fa-f0-9]{12}",
+    ),
+    // 数値
+    ("INT", r"[+-]?\d+"),
+    ("POSINT", r"\b[1-9]\d*\b"),
+    ("NONNEGINT", r"\b\d+\b"),
+    ("NUMBER", r"[+-]?(?:\d+(?:\.\d+)?|\.\d+)"),
+    ("BASE10NUM", r"%{NUMBER}"),
+    // ネットワーク
+    ("IPV4", r"(?:\d{1,3}\.){3}\d{1,3}"),
+    (
+        "IPV6",
+        r"(?:[A-Fa-f0-9]{1,4}:){1,7}[A-Fa-f0-9]{0,4}(?::[A-Fa-f0-9]{1,4})*",
+    ),
+    ("IP", r"(?:%{IPV6}|%{IPV4})"),
+    (
+        "HOSTNAME",
+        r"\b[0-9A-Za-z][0-9A-Za-z-]{0,62}(?:\.[0-9A-Za-z][0-9A-Za-z-]{0,62})*\.?\b",
+    ),
+    ("IPORHOST", r"(?:%{IP}|%{HOSTNAME})"),
+    ("HOSTPORT", r"%{IPORHOST}:%{POSINT}"),
+    ("MAC", r"(?:[A-Fa-f0-9]{2}[:-]){5}[A-Fa-f0-9]{2}"),
+    ("USERNAME", r"[a-zA-Z0-9._-]+"),
+    ("USER", r"%{USERNAME}"),
+    // URI
+    ("URIPROTO", r"[A-Za-z][A-Za-z0-9+.-]*"),
+    ("URIPATH", r"(?:/[^\s?#]*)+"),
+    ("URIPARAM", r"\?[^\s#]*"),
+    ("URIPATHPARAM", r"%{URIPATH}(?:%{URIPARAM})?"),
+    ("URI", r"%{URIPROTO}://[^\s/?#]+(?:%{URIPATHPARAM})?"),
+    // 日時
+    ("YEAR", r"\d{4}"),
+    ("MONTHNUM", r"(?:0?[1-9]|1[0-2])"),
+    ("MONTHDAY", r"(?:0?[1-9]|[12]\d|3[01])"),
+    ("MONTH", r"\b[A-Z][a-z]{2,8}\b"),
+    ("TIME", r"\d{2}:\d{2}:\d{2}(?:[.,]\d+)?"),
+    ("DATE", r"%{YEAR}[-/]%{MONTHNUM}[-/]%{MONTHDAY}"),
+    (
+        "TIMESTAMP_ISO8601",
+        r"%{YEAR}-%{MONTHNUM}-%{MONTHDAY}[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?",
+    ),
+    (
+        "HTTPDATE",
+        r"%{MONTHDAY}/%{MONTH}/%{YEAR}:%{TIME} [+-]\d{4}",
+    ),
+    ("SYSLOGTIMESTAMP", r"%{MONTH} +%{MONTHDAY} %{TIME}"),
+    // ログ
+    (
+        "LOGLEVEL",
+        r"(?i:TRACE|DEBUG|INFO|NOTICE|WARN(?:ING)?|ERROR|ERR|CRIT(?:ICAL)?|FATAL|SEVERE|EMERG(?:ENCY)?|ALERT)",
+    ),
+    ("PROG", r"[\w._/%-]+"),
+];
+
+/// 数値として取り込むパターン
+const NUMERIC_PATTERNS: &[&str] = &["INT", "POSINT", "NONNEGINT", "NUMBER", "BASE10NUM"];
+
+/// フィールドの型変換指定
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldType {
+    Auto,
+    String,
+    Int,
+    Float,
+}
+
+/// コンパイル済みのgrokパターン
+#[derive(Debug)]
+pub struct GrokPattern {
+    regex: Regex,
+    fields: Vec<(String, FieldType)>,
+}
+
+impl GrokPattern {
+    /// "%{IP:client} - [%{HTTPDATE:ts}]" 形式のパターンをコンパイル
+    ///
+    /// `%{...}` 以外の部分はそのままの文字列として照合する。
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        let mut fields = Vec::new();
+        let mut regex_source = String::new();
+        let mut rest = pattern;
+
+        while let Some(start) = rest.find("%{") {
+            regex_source.push_str(&regex::escape(&rest[..start]));
+
+            let end = rest[start..].find('}').ok_or_else(|| {
+                Error::InvalidQuery(format!("Unclosed %{{ in parse pattern: {}", pattern))
+            })? + start;
+
+            let spec = &rest[start + 2..end];
+            regex_source.push_str(&compile_capture(spec, &mut fields)?);
+            rest = &rest[end + 1..];
+        }
+        regex_source.push_str(&regex::escape(rest));
+
+        let regex = Regex::new(&regex_source)
+            .map_err(|e| Error::InvalidQuery(format!("Invalid parse pattern: {}", e)))?;
+
+        Ok(GrokPattern { regex, fields })
+    }
+
+    /// コンパイル済みのパターンをパターン文字列ごとに再利用する
+    ///
+    /// map(.line | parse("...")) のように値ごとに呼ばれても正規表現のコンパイルは1回で済む。
+    pub fn cached(pattern: &str) -> Result<Rc<Self>, Error> {
+        thread_local! {
+            static CACHE: RefCell<HashMap<String, Rc<GrokPattern>>> = RefCell::new(HashMap::new());
+        }
+
+        if let Some(grok) = CACHE.with(|cache| cache.borrow().get(pattern).cloned()) {
+            return Ok(grok);
+        }
+
+        let grok = Rc::new(GrokPattern::new(pattern)?);
+        CACHE.with(|cache| {
+            cache
+                .borrow_mut()
+                .insert(pattern.to_string(), Rc::clone(&grok))
+        });
+        Ok(grok)
+    }
+
+    /// 1行をオブジェクトに変換（マッチしなければNone）
+    pub fn parse_line(&self, line: &str) -> Option<Value> {
+        let captures = self.regex.captures(line)?;
+
+        let mut obj = Map::new();
+        for (i, (name, field_type)) in self.fields.iter().enumerate() {
+            let value = captures
+                .name(&capture_group_name(i))
+                .map(|m| convert_field(m.as_str(), *field_type))
+                .unwrap_or(Value::Null);
+            obj.insert(name.clone(), value);
+        }
+
+        Some(Value::Object(obj))
+    }
+}
+
+/// 組み込みのログ形式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    /// Common Log Format
+    Clf,
+    /// Apache combined（referer/user_agent がなければ CLF として解析）
+    Apache,
+    /// nginx の combined 形式
+    Nginx,
+    /// RFC 3164 の syslog
+    Syslog,
+}
+
+impl LogFormat {
+    /// --input-format で指定する名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogFormat::Clf => "clf",
+            LogFormat::Apache => "apache",
+            LogFormat::Nginx => "nginx",
+            LogFormat::Syslog => "syslog",
+        }
+    }
+}
+
+/// Common Log Format のパターン
+const CLF_PATTERN: &str = r#"%{IPORHOST:client} %{NOTSPACE:ident} %{NOTSPACE:auth} [%{HTTPDATE:time}] "%{WORD:method} %{NOTSPACE:path} %{NOTSPACE:protocol}" %{INT:status} %{NOTSPACE:bytes:int}"#;
+
+/// combined 形式（CLF + referer + user_agent）のパターン
+const COMBINED_PATTERN: &str = r#"%{IPORHOST:client} %{NOTSPACE:ident} %{NOTSPACE:auth} [%{HTTPDATE:time}] "%{WORD:method} %{NOTSPACE:path} %{NOTSPACE:protocol}" %{INT:status} %{NOTSPACE:bytes:int} "%{DATA:referer}" "%{DATA:user_agent}""#;
+
+/// syslog のパターン（pid あり → なしの順に試す）
+const SYSLOG_PATTERNS: &[&str] = &[
+    "%{SYSLOGTIMESTAMP:time} %{IPORHOST:host} %{PROG:program}[%{POSINT:pid}]: %{GREEDYDATA:message}",
+    "%{SYSLOGTIMESTAMP:time} %{IPORHOST:host} %{PROG:program}: %{GREEDYDATA:message}",
+];
+
+/// ログ形式ごとのパーサー
+#[derive(Debug)]
+pub struct LogParser {
+    patterns: Vec<GrokPattern>,
+}
+
+impl LogParser {
+    pub fn new(format: LogFormat) -> Result<Self, Error> {
+        let sources: Vec<&str> = match format {
+            LogFormat::Clf => vec![CLF_PATTERN],
+            LogFormat::Nginx => vec![COMBINED_PATTERN],
+            LogFormat::Apache => vec![COMBINED_PATTERN, CLF_PATTERN],
+            LogFormat::Syslog => SYSLOG_PATTERNS.to_vec(),
+        };
+
+        let patterns = sources
+            .into_iter()
+            .map(GrokPattern::new)
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(LogParser { patterns })
+    }
+
+    /// 1行を解析（"-" は null、アクセスログの時刻は RFC 3339 に正規化）
+    pub fn parse_line(&self, line: &str) -> Option<Value> {
+        let mut record = self
+            .patterns
+            .iter()
+            .find_map(|pattern| pattern.parse_line(line))?;
+
+        if let Value::Object(obj) = &mut record {
+            for value in obj.values_mut() {
+                if value.as_str() == Some("-") {
+                    *value = Value::Null;
+                }
+            }
+
+            if let Some(Value::String(time)) = obj.get("time")
+                && let Some(datetime) = parse_datetime(time)
+            {
+                let normalized = datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+                obj.insert("time".to_string(), Value::String(normalized));
+            }
+        }
+
+        Some(record)
+    }
+}
+
+/// 解析できなかった行（行番号, 内容）
+pub type ParseFailure = (usize, String);
+
+/// ログを行ごとに解析し、解析できなかった行も返す
+pub fn parse_log_lines(
+    content: &str,
+    format: LogFormat,
+) -> Result<(Vec<Value>, Vec<ParseFailure>), Error> {
+    let parser = LogParser::new(format)?;
+    let mut records = Vec::new();
+    let mut failures = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parser.parse_line(line) {
+            Some(record) => records.push(record),
+            None => failures.push((index + 1, line.to_string())),
+        }
+    }
+
+    Ok((records, failures))
+}
+
+/// parse("pattern") を各行に適用（マッチしない行は除外）
+pub fn apply_parse_operation(data: Vec<Value>, pattern: &str) -> Result<Vec<Value>, Error> {
+    let grok = GrokPattern::new(pattern)?;
+
+    let mut results = Vec::new();
+    for item in data {
+        match item {
+            Value::String(line) => {
+                if let Some(record) = grok.parse_line(&line) {
+                    results.push(record);
+                }
+            }
+            _ => {
+                return Err(Error::InvalidQuery(
+                    "parse can only be applied to text lines. Use map(.field | parse(...)) for object fields"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// key=value 形式の1行をオブジェクトに変換（logfmt）
+///
+/// pair_separator が None の場合は空白区切り。引用符付きの値は文字列のまま、
+/// それ以外は CSV と同じ規則で型推論する。値のないキーは true になる。
+pub fn parse_key_values(line: &str, pair_separator: Option<&str>, kv_separator: &str) -> Value {
+    let pair_separator = pair_separator
+        .map(str::trim)
+        .filter(|separator| !separator.is_empty());
+    let mut obj = Map::new();
+    let mut rest = line.trim_start();
+
+    while !rest.is_empty() {
+        // キーを読む（区切り文字か値の区切りまで）
+        let key_end = rest
+            .find(kv_separator)
+            .unwrap_or(rest.len())
+            .min(find_pair_boundary(rest, pair_separator));
+        let key = rest[..key_end].trim();
+        rest = &rest[key_end..];
+
+        let value = if let Some(after_sep) = rest.strip_prefix(kv_separator) {
+            let (value, remaining) = read_kv_value(after_sep, pair_separator);
+            rest = remaining;
+            value
+        } else {
+            Value::Bool(true)
+        };
+
+        if !key.is_empty() {
+            obj.insert(key.to_string(), value);
+        }
+
+        rest = skip_pair_separator(rest, pair_separator);
+    }
+
+    Value::Object(obj)
+}
+
+/// 値を読み取り、残りの文字列と一緒に返す
+fn read_kv_value<'a>(text: &'a str, pair_separator: Option<&str>) -> (Value, &'a str) {
+    if let Some(quoted) = text.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+
+        while let Some((pos, ch)) = chars.next() {
+            match ch {
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, escaped)) => value.push(escaped),
+                    None => value.push('\\'),
+                },
+                '"' => return (Value::String(value), &quoted[pos + 1..]),
+                _ => value.push(ch),
+            }
+        }
+
+        // 閉じ引用符がない場合は行末までを値とする
+        return (Value::String(value), "");
+    }
+
+    let value_end = find_pair_boundary(text, pair_separator);
+    let value = infer_value_type(text[..value_end].trim());
+    (value, &text[value_end..])
+}
+
+/// 次のペア区切りの位置（なければ末尾）
+fn find_pair_boundary(text: &str, pair_separator: Option<&str>) -> usize {
+    match pair_separator {
+        None => text.find(char::is_whitespace),
+        Some(separator) => text.find(separator),
+    }
+    .unwrap_or(text.len())
+}
+
+fn skip_pair_separator<'a>(text: &'a str, pair_separator: Option<&str>) -> &'a str {
+    match pair_separator {
+        None => text.trim_start(),
+        Some(separator) => text
+            .trim_start()
+            .strip_prefix(separator)
+            .unwrap_or(text)
+            .trim_start(),
+    }
+}
+
+/// "logfmt" / "kv" / "kv(\"&\", \"=\")" から区切り文字を取得
+///
+/// ペア区切りが空白のみの場合は任意の空白として扱う。
+pub fn parse_kv_separators(operation: &str) -> Result<(Option<String>, String), Error> {
+    if !operation.contains('(') {
+        return Ok((None, "=".to_string()));
+    }
+
+    let args = parse_operation_arguments(operation)?;
+    let pair_separator = match args.first().map(String::as_str) {
+        None => None,
+        Some(separator) if separator.trim().is_empty() => None,
+        // 前後の空白は区切りの前後で読み飛ばすので、区切り文字自体からは除く
+        Some(separator) => Some(separator.trim().to_string()),
+    };
+    let kv_separator = match args.get(1).map(String::as_str) {
+        None => "=".to_string(),
+        Some("") => {
+            return Err(Error::InvalidQuery(
+                "kv: key/value separator must not be empty".to_string(),
+            ));
+        }
+        Some(separator) => separator.to_string(),
+    };
+
+    if args.len() > 2 {
+        return Err(Error::InvalidQuery(format!(
+            "kv takes at most 2 arguments: {}",
+            operation
+        )));
+    }
+
+    Ok((pair_separator, kv_separator))
+}
+
+/// kv / logfmt をテキスト行に適用（空行は除外）
+pub fn apply_key_value_operation(
+    data: Vec<Value>,
+    pair_separator: Option<&str>,
+    kv_separator: &str,
+) -> Result<Vec<Value>, Error> {
+    data.into_iter()
+        .filter(|item| !matches!(item, Value::String(line) if line.trim().is_empty()))
+        .map(|item| match item {
+            Value::String(line) => Ok(parse_key_values(&line, pair_separator, kv_separator)),
+            _ => Err(Error::InvalidQuery(
+                "kv/logfmt can only be applied to text lines. Use map(.field | logfmt) for object fields"
+                    .to_string(),
+            )),
+        })
+        .collect()
+}
+
+/// parse("...") の引数から引用符を外し、\" と \\ を戻す
+pub fn extract_parse_pattern(argument: &str) -> Result<String, Error> {
+    let argument = argument.trim();
+    let inner = argument
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .or_else(|| {
+            argument
+                .strip_prefix('\'')
+                .and_then(|rest| rest.strip_suffix('\''))
+        })
+        .ok_or_else(|| {
+            Error::InvalidQuery(format!(
+                "parse requires a quoted pattern: parse(\"%{{WORD:field}} ...\"), got: {}",
+                argument
+            ))
+        })?;
+
+    Ok(inner.replace("\\\"", "\"").replace("\\\\", "\\"))
+}
+
+/// "NAME:field:type" を正規表現に変換
+fn compile_capture(spec: &str, fields: &mut Vec<(String, FieldType)>) -> Result<String, Error> {
+    let mut parts = spec.splitn(3, ':');
+    let pattern_name = parts.next().unwrap_or("").trim();
+    let field_name = parts.next().map(str::trim);
+    let type_name = parts.next().map(str::trim);
+
+    let expanded = expand_pattern(pattern_name, 0)?;
+
+    let Some(field_name) = field_name.filter(|name| !name.is_empty()) else {
+        // フィールド名がなければキャプチャしない
+        return Ok(format!("(?:{})", expanded));
+    };
+
+    if !field_name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(Error::InvalidQuery(format!(
+            "Invalid field name in parse pattern: '{}'",
+            field_name
+        )));
+    }
+
+    let field_type = match type_name {
+        None if NUMERIC_PATTERNS.contains(&pattern_name) => FieldType::Auto,
+        None => FieldType::String,
+        Some("int") => FieldType::Int,
+        Some("float") => FieldType::Float,
+        Some("string") => FieldType::String,
+        Some(other) => {
+            return Err(Error::InvalidQuery(format!(
+                "Unknown field type '{}' in parse pattern. Use int, float or string",
+                other
+            )));
+        }
+    };
+
+    let group_name = capture_group_name(fields.len());
+    fields.push((field_name.to_string(), field_type));
+    Ok(format!("(?P<{}>{})", group_name, expanded))
+}
+
+/// 名前付きパターンを再帰的に展開
+fn expand_pattern(name: &str, depth: usize) -> Result<String, Error> {
+    if depth > MAX_PATTERN_DEPTH {
+        return Err(Error::InvalidQuery(format!(
+            "Pattern '{}' is nested too deeply",
+            name
+        )));
+    }
+
+    let definition = GROK_PATTERNS
+        .iter()
+        .find(|(pattern_name, _)| *pattern_name == name)
+        .map(|(_, definition)| *definition)
+        .ok_or_else(|| Error::InvalidQuery(format!("Unknown parse pattern: %{{{}}}", name)))?;
+
+    let mut expanded = String::new();
+    let mut rest = definition;
+    while let Some(start) = rest.find("%{") {
+        expanded.push_str(&rest[..start]);
+        let end = rest[start..].find('}').unwrap_or(rest.len() - start) + start;
+        expanded.push_str(&format!(
+            "(?:{})",
+            expand_pattern(&rest[start + 2..end], depth + 1)?
+        ));
+        rest = &rest[(end + 1).min(rest.len())..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+/// キャプチャグループ名（フィールド名は正規表現の制約を受けないよう番号で管理）
+fn capture_group_name(index: usize) -> String {
+    format!("f{}", index)
+}
+
+fn convert_field(text: &str, field_type: FieldType) -> Value {
+    match field_type {
+        FieldType::String => Value::String(text.to_string()),
+        FieldType::Int => text
+            .parse::<i64>()
+            .map(|n| Value::Number(n.into()))
+            .unwrap_or(Value::Null),
+        FieldType::Float => text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        FieldType::Auto => {
+            if let Ok(n) = text.parse::<i64>() {
+                Value::Number(n.into())
+            } else {
+                convert_field(text, FieldType::Float)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_access_log() {
+        let pattern = r#"%{IP:client} - - [%{HTTPDATE:ts}] "%{WORD:method} %{URIPATHPARAM:path} %{NOTSPACE}" %{INT:status} %{INT:bytes}"#;
+        let line = r#"192.168.1.10 - - [14/Mar/2024:10:05:00 +0900] "GET /api/users?page=2 HTTP/1.1" 200 5120"#;
+
+        let grok = GrokPattern::new(pattern).unwrap();
+        let record = grok.parse_line(line).unwrap();
+
+        assert_eq!(
+            record,
+            json!({
+                "client": "192.168.1.10",
+                "ts": "14/Mar/2024:10:05:00 +0900",
+                "method": "GET",
+                "path": "/api/users?page=2",
+                "status": 200,
+                "bytes": 5120
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_operation_skips_unmatched_lines() {
+        let data = vec![
+            json!("2024-03-14 10:00:01 ERROR db timeout after 3.5s"),
+            json!("-- not a log line --"),
+            json!("2024-03-14 10:00:02 info started"),
+        ];
+
+        let result = apply_parse_operation(
+            data,
+            "%{TIMESTAMP_ISO8601:ts} %{LOGLEVEL:level} %{GREEDYDATA:message}",
+        )
+        .unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0]["level"], json!("ERROR"));
+        assert_eq!(result[1]["message"], json!("started"));
+    }
+
+    #[test]
+    fn test_parse_field_types_and_errors() {
+        let grok =
+            GrokPattern::new("%{NOTSPACE:id:int} %{NOTSPACE:ratio:float} %{INT:code:string}")
+                .unwrap();
+        let record = grok.parse_line("42 0.5 007").unwrap();
+        assert_eq!(record, json!({"id": 42, "ratio": 0.5, "code": "007"}));
+
+        assert!(GrokPattern::new("%{NOPE:x}").is_err());
+        assert!(GrokPattern::cached("%{NOPE:x}").is_err());
+
+        let first = GrokPattern::cached("%{WORD:name}").unwrap();
+        let second = GrokPattern::cached("%{WORD:name}").unwrap();
+        assert!(Rc::ptr_eq(&first, &second));
+        assert!(GrokPattern::new("%{WORD:x").is_err());
+        assert!(GrokPattern::new("%{WORD:x:bool}").is_err());
+        assert!(apply_parse_operation(vec![json!({"a": 1})], "%{WORD:x}").is_err());
+    }
+
+    #[test]
+    fn test_parse_logfmt_line() {
+        let line =
+            r#"level=info msg="request \"done\"" dur=12ms status=200 ratio=0.5 cached empty="#;
+        let record = parse_key_values(line, None, "=");
+
+        assert_eq!(
+            record,
+            json!({
+                "level": "info",
+                "msg": "request \"done\"",
+                "dur": "12ms",
+                "status": 200,
+                "ratio": 0.5,
+                "cached": true,
+                "empty": null
+            })
+        );
+
+        // 引用符付きの値は型推論しない
+        let record = parse_key_values(r#"id="42""#, None, "=");
+        assert_eq!(record, json!({"id": "42"}));
+    }
+
+    #[test]
+    fn test_parse_key_values_with_custom_separators() {
+        let (pair, kv) = parse_kv_separators(r#"kv("&", "=")"#).unwrap();
+        let record = parse_key_values("a=1&b=two&flag", pair.as_deref(), &kv);
+        assert_eq!(record, json!({"a": 1, "b": "two", "flag": true}));
+
+        let (pair, kv) = parse_kv_separators(r#"kv(";", ": ")"#).unwrap();
+        let record = parse_key_values("host: web-1; port: 8080", pair.as_deref(), &kv);
+        assert_eq!(record, json!({"host": "web-1", "port": 8080}));
+
+        // 空白を含むペア区切りは、区切りの前後の空白を許容する
+        let (pair, kv) = parse_kv_separators(r#"kv(" ;")"#).unwrap();
+        assert_eq!(pair.as_deref(), Some(";"));
+        let record = parse_key_values("a=1 ;b=2;c=3 ; d=4", pair.as_deref(), &kv);
+        assert_eq!(record, json!({"a": 1, "b": 2, "c": 3, "d": 4}));
+        let record = parse_key_values("a=1 ;b=2", Some(" ; "), "=");
+        assert_eq!(record, json!({"a": 1, "b": 2}));
+
+        let result = apply_key_value_operation(
+            vec![json!("level=warn n=1"), json!("  "), json!("level=info")],
+            None,
+            "=",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            vec![json!({"level": "warn", "n": 1}), json!({"level": "info"})]
+        );
+    }
+
+    #[test]
+    fn test_parse_log_lines() {
+        let content = concat!(
+            r#"203.0.113.45 - - [18/Jul/2024:09:16:45 +0000] "POST /api/v1/orders HTTP/1.1" 201 342 "https://shop.example.com/cart" "Mozilla/5.0 (X11)""#,
+            "\n",
+            r#"192.168.1.100 - frank [18/Jul/2024:09:15:30 +0900] "GET /health HTTP/1.1" 304 -"#,
+            "\n",
+            "garbage line\n",
+        );
+
+        let (records, failures) = parse_log_lines(content, LogFormat::Apache).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(failures, vec![(3, "garbage line".to_string())]);
+
+        assert_eq!(
+            records[0],
+            json!({
+                "client": "203.0.113.45",
+                "ident": null,
+                "auth": null,
+                "time": "2024-07-18T09:16:45Z",
+                "method": "POST",
+                "path": "/api/v1/orders",
+                "protocol": "HTTP/1.1",
+                "status": 201,
+                "bytes": 342,
+                "referer": "https://shop.example.com/cart",
+                "user_agent": "Mozilla/5.0 (X11)"
+            })
+        );
+        assert_eq!(records[1]["auth"], json!("frank"));
+        assert_eq!(records[1]["bytes"], Value::Null);
+        assert_eq!(records[1]["time"], json!("2024-07-18T09:15:30+09:00"));
+
+        // nginx は combined 形式のみ
+        let (records, failures) = parse_log_lines(content, LogFormat::Nginx).unwrap();
+        assert_eq!((records.len(), failures.len()), (1, 2));
+    }
+
+    #[test]
+    fn test_parse_syslog_lines() {
+        let content = "Jan 15 10:30:46 server01 sshd[1234]: Accepted password for user\nJan  5 10:30:47 server01 kernel: TCP window shrunk\n";
+
+        let (records, failures) = parse_log_lines(content, LogFormat::Syslog).unwrap();
+        assert!(failures.is_empty());
+        assert_eq!(
+            records[0],
+            json!({
+                "time": "Jan 15 10:30:46",
+                "host": "server01",
+                "program": "sshd",
+                "pid": 1234,
+                "message": "Accepted password for user"
+            })
+        );
+        assert_eq!(records[1]["program"], json!("kernel"));
+        assert_eq!(records[1]["time"], json!("Jan  5 10:30:47"));
+    }
+}
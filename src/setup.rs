@@ -1,75 +1,533 @@
-use std::io::{self, Read};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::Path;
 
 use clap::Parser;
+use serde::Deserialize;
 use serde_json::Value;
 
-use crate::{Args, Error, OutputFormat};
+use crate::{
+    Args, DEFAULT_JSON_INDENT, Error, LogFormat, OutputFormat, ZIP_MAGIC, is_spreadsheet,
+    parse_key_values, parse_log_lines, parse_xml, read_decompressed, read_spreadsheet,
+    value_to_string,
+};
+
+/// Parquet ファイルの先頭（と末尾）のマジックナンバー
+///
+/// `parquet` フィーチャーなしでも判定してエラーを出せるよう、ここで定義する。
+pub const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+/// Arrow IPC ファイル形式（Feather v2）のマジックナンバー
+pub const ARROW_FILE_MAGIC: &[u8] = b"ARROW1";
+
+/// Arrow IPC ストリーム形式の先頭（継続マーカー）
+const ARROW_STREAM_CONTINUATION: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF];
 
 pub fn setup() -> Result<(Value, String, OutputFormat), Error> {
     let args = Args::parse();
 
-    let content = if let Some(path) = args.path {
-        std::fs::read_to_string(path)?
+    // gzip / zstd / bzip2 / xz は形式判定の前に展開する
+    let bytes = if let Some(path) = &args.path {
+        read_decompressed(io::BufReader::new(std::fs::File::open(path)?))?
     } else {
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)?;
-        buffer
+        read_decompressed(io::stdin().lock())?
     };
 
+    let format = resolve_output_format(&args)?;
+
+    let options = InputOptions::from_args(&args)?;
+
+    // Parquet / Arrow IPC はテキストとして読まずに直接変換
+    if let Some(data) = read_columnar_input(&bytes)? {
+        return Ok((data, args.query, format));
+    }
+
+    // xlsx / ods などのスプレッドシートはセルの型を保って読み込む
+    if is_spreadsheet(&bytes) {
+        reject_csv_only_options(&args)?;
+        let rows = read_spreadsheet(&bytes, args.sheet.as_deref())?;
+        return Ok((
+            spreadsheet_rows_to_json(rows, &options.csv),
+            args.query,
+            format,
+        ));
+    }
+
+    if bytes.starts_with(ZIP_MAGIC) {
+        return Err(Error::InvalidFormat(
+            "ZIP archive is not a spreadsheet (xlsx, xlsb or ods); other archives are not supported"
+                .to_string(),
+        ));
+    }
+
+    let content =
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
     let input_format = if args.text {
         InputFormat::Text
+    } else if args.logfmt {
+        InputFormat::Logfmt
+    } else if let Some(name) = &args.input_format {
+        parse_input_format_name(name)?
+    } else if let Some(format) = args.path.as_deref().and_then(detect_format_from_extension) {
+        format
     } else {
-        detect_input_format(&content)
+        // 拡張子がない場合（標準入力など）のみ内容から判定
+        match detect_input_format(&content) {
+            // 区切り文字を判定できなくても、CSV のオプションがあれば CSV として扱う
+            (InputFormat::Text, _) if options.csv.is_customized() => InputFormat::Csv,
+            (format, notice) => {
+                if let Some(notice) = notice {
+                    eprintln!("Notice: {} (use --input-format to override)", notice);
+                }
+                format
+            }
+        }
     };
 
-    let data = parse_content(&content, input_format)?;
+    check_csv_options(&input_format, &options.csv)?;
+
+    // YAML の場合は document_index を追加したかを出力側に伝える
+    let (data, format) = match input_format {
+        InputFormat::Yaml => {
+            let (data, indexed) = parse_yaml_documents(&content)?;
+            let format = match format {
+                OutputFormat::Yaml { documents, .. } => OutputFormat::Yaml {
+                    documents,
+                    indexed_documents: indexed,
+                },
+                format => format,
+            };
+            (data, format)
+        }
+        _ => (parse_content(&content, input_format, &options)?, format),
+    };
     let query = args.query;
 
+    // debug
+    // debug_json_order(&json);
+    Ok((data, query, format))
+}
+
+/// スプレッドシートのセルは型を持つため、CSV のテキスト解析用のオプションは使えない
+fn reject_csv_only_options(args: &Args) -> Result<(), Error> {
+    let used: Vec<&str> = [
+        ("--delimiter", args.delimiter.is_some()),
+        ("--quote", args.quote.is_some()),
+        ("--no-infer", args.no_infer),
+        ("--schema", args.schema.is_some()),
+        ("--col-type", args.col_type.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(flag, is_used)| is_used.then_some(flag))
+    .collect();
+
+    if used.is_empty() {
+        return Ok(());
+    }
+
+    Err(Error::InvalidFormat(format!(
+        "{} cannot be used with spreadsheet input (only --sheet, --no-header and --skip-rows apply)",
+        used.join(", ")
+    )))
+}
+
+/// CSV のオプションは CSV / TSV として読む場合にのみ適用できる
+fn check_csv_options(input_format: &InputFormat, options: &CsvOptions) -> Result<(), Error> {
+    if !options.is_customized() || matches!(input_format, InputFormat::Csv | InputFormat::Tsv) {
+        return Ok(());
+    }
+
+    Err(Error::InvalidFormat(
+        "--delimiter, --quote, --no-header, --skip-rows, --no-infer, --schema and --col-type only apply to delimited (CSV or TSV) input; use --input-format csv to read this input as CSV"
+            .to_string(),
+    ))
+}
+
+/// --format と出力オプション（--yaml-documents, --compact など）から出力形式を決定
+fn resolve_output_format(args: &Args) -> Result<OutputFormat, Error> {
     let format = args
         .format
         .parse::<OutputFormat>()
         .map_err(|e| Error::InvalidFormat(e.to_string()))?;
 
-    // debug
-    // debug_json_order(&json);
-    Ok((data, query, format))
+    let json_options = args.compact || args.indent.is_some() || args.raw_output;
+    let indent = match args.indent {
+        _ if args.compact => None,
+        Some(0) => None,
+        Some(indent) => Some(indent),
+        None => Some(DEFAULT_JSON_INDENT),
+    };
+
+    match format {
+        OutputFormat::Yaml { .. } => Ok(OutputFormat::Yaml {
+            documents: args.yaml_documents,
+            indexed_documents: false,
+        }),
+        _ if args.yaml_documents => Err(Error::InvalidFormat(
+            "--yaml-documents requires --format yaml".to_string(),
+        )),
+        // JSON 用のオプションが指定された場合、auto は JSON として出力
+        OutputFormat::Json { .. } | OutputFormat::Auto if json_options => Ok(OutputFormat::Json {
+            indent,
+            raw: args.raw_output,
+        }),
+        OutputFormat::JsonLines { .. } if args.indent.is_some_and(|indent| indent > 0) => Err(
+            Error::InvalidFormat("--indent cannot be used with --format jsonl".to_string()),
+        ),
+        OutputFormat::JsonLines { .. } => Ok(OutputFormat::JsonLines {
+            raw: args.raw_output,
+        }),
+        OutputFormat::Json { .. } | OutputFormat::Auto => Ok(format),
+        _ if json_options => Err(Error::InvalidFormat(
+            "--compact, --indent and --raw-output require --format json or jsonl".to_string(),
+        )),
+        format => Ok(format),
+    }
 }
 
 #[derive(Debug)]
 enum InputFormat {
     Json,
+    JsonLines,
     Yaml,
+    Toml,
+    Xml,
     Csv,
+    Tsv,
     Text,
+    Logfmt,
+    Log(LogFormat),
 }
 
-fn detect_input_format(content: &str) -> InputFormat {
-    let trimmed = content.trim();
+/// 先頭のマジックナンバーから Parquet / Arrow IPC を判定して読み込む
+fn read_columnar_input(bytes: &[u8]) -> Result<Option<Value>, Error> {
+    let is_parquet = bytes.starts_with(PARQUET_MAGIC);
+    let is_arrow =
+        bytes.starts_with(ARROW_FILE_MAGIC) || bytes.starts_with(ARROW_STREAM_CONTINUATION);
+
+    if !is_parquet && !is_arrow {
+        return Ok(None);
+    }
+
+    #[cfg(feature = "parquet")]
+    {
+        if is_parquet {
+            crate::read_parquet(bytes).map(Some)
+        } else {
+            crate::read_arrow_ipc(bytes).map(Some)
+        }
+    }
+
+    #[cfg(not(feature = "parquet"))]
+    Err(Error::InvalidFormat(
+        "Parquet and Arrow IPC input require hawk to be built with the `parquet` feature"
+            .to_string(),
+    ))
+}
+
+/// 入力形式ごとの読み込み設定
+#[derive(Debug, Default)]
+struct InputOptions {
+    csv: CsvOptions,
+    /// 常に配列として扱う XML 要素名
+    xml_array_elements: Vec<String>,
+}
+
+impl InputOptions {
+    fn from_args(args: &Args) -> Result<Self, Error> {
+        Ok(Self {
+            csv: CsvOptions::from_args(args)?,
+            xml_array_elements: args
+                .xml_array
+                .as_deref()
+                .map(|names| {
+                    names
+                        .split(',')
+                        .map(|name| name.trim().to_string())
+                        .filter(|name| !name.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// CSV の読み込み設定
+#[derive(Debug, Clone)]
+struct CsvOptions {
+    /// 区切り文字（None の場合は自動判定）
+    delimiter: Option<u8>,
+    quote: u8,
+    has_headers: bool,
+    skip_rows: usize,
+    /// false の場合は全ての値を文字列のまま扱う
+    infer_types: bool,
+    /// 列ごとに指定された型（推論より優先）
+    column_types: HashMap<String, ColumnType>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            quote: b'"',
+            has_headers: true,
+            skip_rows: 0,
+            infer_types: true,
+            column_types: HashMap::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    fn from_args(args: &Args) -> Result<Self, Error> {
+        let defaults = Self::default();
+
+        Ok(Self {
+            delimiter: args
+                .delimiter
+                .as_deref()
+                .map(|value| parse_csv_char(value, "--delimiter"))
+                .transpose()?,
+            quote: args
+                .quote
+                .as_deref()
+                .map(|value| parse_csv_char(value, "--quote"))
+                .transpose()?
+                .unwrap_or(defaults.quote),
+            has_headers: !args.no_header,
+            skip_rows: args.skip_rows,
+            infer_types: !args.no_infer,
+            column_types: load_column_types(args)?,
+        })
+    }
+
+    fn is_customized(&self) -> bool {
+        self.delimiter.is_some()
+            || self.quote != b'"'
+            || !self.has_headers
+            || self.skip_rows > 0
+            || !self.infer_types
+            || !self.column_types.is_empty()
+    }
+}
+
+/// CSV の列の型
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnType {
+    String,
+    Int,
+    Float,
+    Bool,
+}
 
-    // CSV判定を最初に行う（シンプルな形式から）
-    if is_likely_csv(trimmed) {
-        return InputFormat::Csv;
+impl std::str::FromStr for ColumnType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "string" | "str" => Ok(ColumnType::String),
+            "int" | "integer" => Ok(ColumnType::Int),
+            "float" | "number" => Ok(ColumnType::Float),
+            "bool" | "boolean" => Ok(ColumnType::Bool),
+            _ => Err(Error::InvalidFormat(format!(
+                "Invalid column type: {}. Valid options: string, int, float, bool",
+                s
+            ))),
+        }
+    }
+}
+
+impl ColumnType {
+    fn name(&self) -> &'static str {
+        match self {
+            ColumnType::String => "string",
+            ColumnType::Int => "int",
+            ColumnType::Float => "float",
+            ColumnType::Bool => "bool",
+        }
+    }
+}
+
+/// --schema ファイルと --col-type から列の型を読み込む（--col-type が優先）
+fn load_column_types(args: &Args) -> Result<HashMap<String, ColumnType>, Error> {
+    let mut column_types = HashMap::new();
+
+    if let Some(path) = &args.schema {
+        let schema: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let Value::Object(columns) = schema else {
+            return Err(Error::InvalidFormat(
+                "--schema must be a JSON object mapping column names to types".to_string(),
+            ));
+        };
+
+        for (column, column_type) in columns {
+            let Value::String(column_type) = column_type else {
+                return Err(Error::InvalidFormat(format!(
+                    "Column type for '{}' must be a string",
+                    column
+                )));
+            };
+            column_types.insert(column, column_type.parse()?);
+        }
     }
 
-    // JSON判定（厳密にチェック）- YAMLより先に判定
+    if let Some(spec) = &args.col_type {
+        for entry in spec.split(',').filter(|entry| !entry.trim().is_empty()) {
+            let (column, column_type) = entry.split_once('=').ok_or_else(|| {
+                Error::InvalidFormat(format!(
+                    "--col-type expects column=type pairs, got: {}",
+                    entry
+                ))
+            })?;
+            column_types.insert(column.trim().to_string(), column_type.parse()?);
+        }
+    }
+
+    Ok(column_types)
+}
+
+/// "\t" / "tab" / 1文字の ASCII を区切り文字に変換
+fn parse_csv_char(value: &str, option: &str) -> Result<u8, Error> {
+    match value {
+        "\\t" | "\t" | "tab" => Ok(b'\t'),
+        _ if value.len() == 1 => Ok(value.as_bytes()[0]),
+        _ => Err(Error::InvalidFormat(format!(
+            "{} must be a single ASCII character, got: {}",
+            option, value
+        ))),
+    }
+}
+
+/// --input-format の値を InputFormat に変換
+fn parse_input_format_name(name: &str) -> Result<InputFormat, Error> {
+    match name.to_lowercase().as_str() {
+        "json" => Ok(InputFormat::Json),
+        "jsonl" | "ndjson" => Ok(InputFormat::JsonLines),
+        "yaml" | "yml" => Ok(InputFormat::Yaml),
+        "toml" => Ok(InputFormat::Toml),
+        "xml" => Ok(InputFormat::Xml),
+        "csv" => Ok(InputFormat::Csv),
+        "tsv" => Ok(InputFormat::Tsv),
+        "text" => Ok(InputFormat::Text),
+        "logfmt" => Ok(InputFormat::Logfmt),
+        "clf" => Ok(InputFormat::Log(LogFormat::Clf)),
+        "apache" => Ok(InputFormat::Log(LogFormat::Apache)),
+        "nginx" => Ok(InputFormat::Log(LogFormat::Nginx)),
+        "syslog" => Ok(InputFormat::Log(LogFormat::Syslog)),
+        _ => Err(Error::InvalidFormat(format!(
+            "Invalid input format: {}. Valid options: json, jsonl, yaml, toml, xml, csv, tsv, text, logfmt, clf, apache, nginx, syslog",
+            name
+        ))),
+    }
+}
+
+/// 圧縮ファイルの拡張子（app.json.gz の場合は .json で判定する）
+const COMPRESSION_EXTENSIONS: [&str; 6] = ["gz", "gzip", "zst", "zstd", "bz2", "xz"];
+
+/// 拡張子から入力形式を判定（.log など形式が決まらない拡張子は内容から判定）
+fn detect_format_from_extension(path: &Path) -> Option<InputFormat> {
+    let mut extension = path.extension()?.to_str()?.to_lowercase();
+
+    if COMPRESSION_EXTENSIONS.contains(&extension.as_str()) {
+        let stem = Path::new(path.file_stem()?);
+        extension = stem.extension()?.to_str()?.to_lowercase();
+    }
+
+    match extension.as_str() {
+        "json" => Some(InputFormat::Json),
+        "jsonl" | "ndjson" => Some(InputFormat::JsonLines),
+        "yaml" | "yml" => Some(InputFormat::Yaml),
+        "toml" => Some(InputFormat::Toml),
+        "xml" => Some(InputFormat::Xml),
+        "csv" => Some(InputFormat::Csv),
+        "tsv" | "tab" => Some(InputFormat::Tsv),
+        "txt" => Some(InputFormat::Text),
+        _ => None,
+    }
+}
+
+/// 内容から入力形式を判定（複数の形式に当てはまる場合は通知メッセージも返す）
+fn detect_input_format(content: &str) -> (InputFormat, Option<String>) {
+    let trimmed = content.trim();
+
+    // JSON判定（厳密にチェック）- カンマを含むため CSV より先に判定
     if (trimmed.starts_with('{') && trimmed.ends_with('}'))
         || (trimmed.starts_with('[') && trimmed.ends_with(']'))
     {
         // さらに、全体がJSONとして有効かチェック
         if serde_json::from_str::<serde_json::Value>(trimmed).is_ok() {
-            return InputFormat::Json;
+            return (InputFormat::Json, None);
+        }
+
+        // JSON Lines / 連結された JSON ストリーム
+        if is_json_stream(trimmed) {
+            return (InputFormat::JsonLines, None);
         }
     }
 
-    // YAML判定 - より厳格な条件に変更
-    if is_structured_yaml(trimmed) {
-        return InputFormat::Yaml;
+    // XML判定
+    if trimmed.starts_with('<') && trimmed.ends_with('>') && parse_xml(trimmed, &[]).is_ok() {
+        return (InputFormat::Xml, None);
+    }
+
+    // TOML判定 - 配列を含むことが多いため CSV より先に判定
+    if is_likely_toml(trimmed) {
+        return (InputFormat::Toml, None);
+    }
+
+    let looks_like_csv = detect_csv_delimiter(trimmed).is_some();
+    let looks_like_yaml = is_structured_yaml(trimmed) && is_yaml_collection(trimmed);
+
+    // 値にカンマを含む YAML は CSV にも見えるため、YAML として構造を持つ方を優先
+    match (looks_like_yaml, looks_like_csv) {
+        (true, true) => (
+            InputFormat::Yaml,
+            Some("input looks like both YAML and CSV; reading it as YAML".to_string()),
+        ),
+        (true, false) => (InputFormat::Yaml, None),
+        (false, true) => (InputFormat::Csv, None),
+        // 上記のいずれにも該当しない場合はText
+        (false, false) => (InputFormat::Text, None),
     }
+}
+
+/// 最初のドキュメントが YAML のマッピングかシーケンスとして読めるか
+fn is_yaml_collection(content: &str) -> bool {
+    serde_yaml::Deserializer::from_str(content)
+        .next()
+        .and_then(|document| Value::deserialize(document).ok())
+        .is_some_and(|value| value.is_object() || value.is_array())
+}
+
+// 空白区切りで複数の JSON 値が並んでいるかを判定
+fn is_json_stream(content: &str) -> bool {
+    let mut count = 0;
+
+    for value in serde_json::Deserializer::from_str(content).into_iter::<Value>() {
+        if value.is_err() {
+            return false;
+        }
+        count += 1;
+    }
+
+    count > 1
+}
+
+// [section] ヘッダーを持ち、TOML として解析できるかを判定
+fn is_likely_toml(content: &str) -> bool {
+    let has_section_header = content.lines().any(|line| {
+        let line = line.trim();
+        line.starts_with('[') && line.ends_with(']') && !line.contains(',')
+    });
 
-    // 上記のいずれにも該当しない場合はText
-    InputFormat::Text
+    has_section_header && content.parse::<toml::Table>().is_ok()
 }
 
+/// Kubernetes や Docker Compose などで使われるトップレベルキー
+const YAML_MARKER_KEYS: [&str; 4] = ["apiVersion:", "kind:", "version:", "services:"];
+
 // 構造化されたYAMLかどうかを厳格に判定
 fn is_structured_yaml(content: &str) -> bool {
     let lines: Vec<&str> = content.lines().collect();
@@ -78,12 +536,12 @@ fn is_structured_yaml(content: &str) -> bool {
         return false;
     }
 
-    // Kubernetes/Docker Compose等の明確なYAMLマーカー
-    if content.contains("apiVersion:")
-        || content.contains("kind:")
-        || content.contains("version:")
-        || content.contains("services:")
-    {
+    // Kubernetes/Docker Compose等の明確なYAMLマーカー（行頭のトップレベルキーのみ）
+    if lines.iter().any(|line| {
+        YAML_MARKER_KEYS
+            .iter()
+            .any(|marker| line.starts_with(marker))
+    }) {
         return true;
     }
 
@@ -164,19 +622,68 @@ fn is_valid_yaml_line(line: &str) -> bool {
     false
 }
 
-fn parse_content(content: &str, format: InputFormat) -> Result<Value, Error> {
+fn parse_content(
+    content: &str,
+    format: InputFormat,
+    options: &InputOptions,
+) -> Result<Value, Error> {
     match format {
-        InputFormat::Json => serde_json::from_str(content).map_err(Error::Json),
-        InputFormat::Yaml => {
-            // 複数ドキュメントに対応
-            if content.contains("---") {
-                parse_multi_document_yaml(content)
-            } else {
-                serde_yaml::from_str(content).map_err(Error::Yaml)
-            }
-        }
-        InputFormat::Csv => parse_csv_to_json(content),
+        InputFormat::Json => match serde_json::from_str(content) {
+            Ok(value) => Ok(value),
+            // .json という名前の JSON Lines も受け付ける
+            Err(_) if is_json_stream(content.trim()) => parse_json_stream_to_json(content),
+            Err(e) => Err(Error::Json(e)),
+        },
+        InputFormat::JsonLines => parse_json_stream_to_json(content),
+        InputFormat::Yaml => parse_yaml_documents(content).map(|(value, _)| value),
+        InputFormat::Toml => parse_toml_to_json(content),
+        InputFormat::Xml => parse_xml(content, &options.xml_array_elements),
+        InputFormat::Csv => parse_csv_to_json(content, &options.csv),
+        InputFormat::Tsv => parse_csv_to_json(
+            content,
+            &CsvOptions {
+                delimiter: Some(options.csv.delimiter.unwrap_or(b'\t')),
+                ..options.csv.clone()
+            },
+        ),
         InputFormat::Text => parse_text_to_json(content),
+        InputFormat::Logfmt => parse_logfmt_to_json(content),
+        InputFormat::Log(log_format) => parse_log_to_json(content, log_format),
+    }
+}
+
+fn parse_json_stream_to_json(content: &str) -> Result<Value, Error> {
+    // JSON Lines（1行1値）と空白区切りの連結 JSON の両方を配列にまとめる
+    let values = serde_json::Deserializer::from_str(content)
+        .into_iter::<Value>()
+        .collect::<Result<Vec<Value>, _>>()
+        .map_err(Error::Json)?;
+
+    Ok(Value::Array(values))
+}
+
+fn parse_toml_to_json(content: &str) -> Result<Value, Error> {
+    let table: toml::Table = content.parse()?;
+    Ok(toml_to_json(toml::Value::Table(table)))
+}
+
+/// TOML の値を JSON に変換（日時は文字列として扱う）
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => serde_json::Number::from_f64(f)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => Value::Array(array.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
     }
 }
 
@@ -191,67 +698,222 @@ fn parse_text_to_json(content: &str) -> Result<Value, Error> {
     Ok(Value::Array(lines))
 }
 
-fn parse_multi_document_yaml(content: &str) -> Result<Value, Error> {
-    let documents: Vec<&str> = content
-        .split("---")
-        .map(|doc| doc.trim())
-        .filter(|doc| !doc.is_empty())
+fn parse_logfmt_to_json(content: &str) -> Result<Value, Error> {
+    // 各行を key=value のオブジェクトに変換（空行は除外）
+    let records: Vec<Value> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_key_values(line, None, "="))
         .collect();
 
-    let mut parsed_docs = Vec::new();
+    Ok(Value::Array(records))
+}
+
+/// 解析できなかった行の警告を表示する上限
+const MAX_REPORTED_LOG_FAILURES: usize = 5;
 
-    for doc in documents {
-        let parsed: Value = serde_yaml::from_str(doc).map_err(Error::Yaml)?;
-        parsed_docs.push(parsed);
+fn parse_log_to_json(content: &str, log_format: LogFormat) -> Result<Value, Error> {
+    let (records, failures) = parse_log_lines(content, log_format)?;
+
+    // 解析できなかった行は処理を止めずに stderr へ報告
+    for (line_number, line) in failures.iter().take(MAX_REPORTED_LOG_FAILURES) {
+        eprintln!(
+            "Warning: line {} is not valid {} log format, skipped: {}",
+            line_number,
+            log_format.name(),
+            line
+        );
+    }
+    if failures.len() > MAX_REPORTED_LOG_FAILURES {
+        eprintln!(
+            "Warning: {} more unparseable lines skipped",
+            failures.len() - MAX_REPORTED_LOG_FAILURES
+        );
     }
 
-    // 複数ドキュメントを配列として返す
-    Ok(Value::Array(parsed_docs))
+    Ok(Value::Array(records))
 }
 
-fn is_likely_csv(content: &str) -> bool {
+/// 複数ドキュメントの YAML で、各ドキュメントの位置を入れるキー
+pub const YAML_DOCUMENT_INDEX_KEY: &str = "document_index";
+
+/// YAML のドキュメントストリームを読み込む
+///
+/// ドキュメントが1つならその値、複数なら配列を返す。複数の場合、オブジェクトの
+/// ドキュメントにはストリーム内での 0 始まりの位置を `document_index` として追加し、
+/// 追加したかどうかも返す。既に `document_index` を持つドキュメントがある場合は
+/// 上書きせず、どのドキュメントにも追加しない。
+fn parse_yaml_documents(content: &str) -> Result<(Value, bool), Error> {
+    let mut documents = Vec::new();
+    for (index, document) in serde_yaml::Deserializer::from_str(content).enumerate() {
+        let value = Value::deserialize(document)?;
+        // 末尾の "---" などによる空のドキュメントは除外（位置は元のストリームのまま）
+        if !value.is_null() {
+            documents.push((index, value));
+        }
+    }
+
+    if documents.len() <= 1 {
+        let value = documents.pop().map_or(Value::Null, |(_, value)| value);
+        return Ok((value, false));
+    }
+
+    let has_own_index = documents.iter().any(|(_, document)| {
+        document
+            .as_object()
+            .is_some_and(|map| map.contains_key(YAML_DOCUMENT_INDEX_KEY))
+    });
+    if has_own_index {
+        eprintln!(
+            "Notice: YAML documents already have a '{}' field; document positions are not added",
+            YAML_DOCUMENT_INDEX_KEY
+        );
+    }
+
+    let documents = documents
+        .into_iter()
+        .map(|(index, mut document)| {
+            if !has_own_index && let Value::Object(map) = &mut document {
+                map.insert(YAML_DOCUMENT_INDEX_KEY.to_string(), Value::from(index));
+            }
+            document
+        })
+        .collect();
+
+    Ok((Value::Array(documents), !has_own_index))
+}
+
+/// 自動判定の対象とする区切り文字
+const CSV_DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+/// 先頭数行から CSV の区切り文字を推定（CSV でなければ None）
+fn detect_csv_delimiter(content: &str) -> Option<u8> {
     let lines: Vec<&str> = content.lines().take(5).collect();
 
-    if lines.is_empty() {
-        return false;
+    let mut best: Option<(u8, usize)> = None;
+    for delimiter in CSV_DELIMITERS {
+        if !has_consistent_delimiters(&lines, delimiter) {
+            continue;
+        }
+
+        // 最初の行で最も多く現れる区切り文字を採用
+        let count = count_delimiters(lines[0], delimiter);
+        if best.is_none_or(|(_, best_count)| count > best_count) {
+            best = Some((delimiter, count));
+        }
     }
 
+    best.map(|(delimiter, _)| delimiter)
+}
+
+fn has_consistent_delimiters(lines: &[&str], delimiter: u8) -> bool {
+    let Some(first_line) = lines.first() else {
+        return false;
+    };
+
     // 最初の行をヘッダーとして想定
-    let first_line = lines[0];
-    let comma_count = first_line.matches(',').count();
+    let expected = count_delimiters(first_line, delimiter);
+    if expected == 0 {
+        return false;
+    }
 
-    // カンマが1個以上あり、他の行も同じような構造
-    if comma_count > 0 {
+    if delimiter == b',' {
         // 他の行も同じようなカンマ数か確認
         lines.iter().skip(1).all(|line| {
-            let line_comma_count = line.matches(',').count();
-            (line_comma_count as i32 - comma_count as i32).abs() <= 1
+            let line_count = count_delimiters(line, delimiter);
+            (line_count as i32 - expected as i32).abs() <= 1
         })
     } else {
-        false
+        // カンマ以外はテキストとの誤判定を避けるため、複数行で列数が一致する場合のみ
+        lines.len() > 1
+            && lines
+                .iter()
+                .all(|line| count_delimiters(line, delimiter) == expected)
     }
 }
 
-fn parse_csv_to_json(content: &str) -> Result<Value, Error> {
-    let mut reader = csv::Reader::from_reader(content.as_bytes());
+/// クォート内を除いて区切り文字を数える
+fn count_delimiters(line: &str, delimiter: u8) -> usize {
+    let mut in_quotes = false;
+    let mut count = 0;
+
+    for byte in line.bytes() {
+        if byte == b'"' {
+            in_quotes = !in_quotes;
+        } else if byte == delimiter && !in_quotes {
+            count += 1;
+        }
+    }
 
-    // ヘッダーを取得
-    let headers: Vec<String> = reader
-        .headers()
-        .map_err(Error::Csv)?
+    count
+}
+
+fn parse_csv_to_json(content: &str, options: &CsvOptions) -> Result<Value, Error> {
+    // Excel 等が付与する BOM と、ヘッダー前の不要な行を取り除く
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let content = skip_lines(content, options.skip_rows);
+
+    let delimiter = options
+        .delimiter
+        .or_else(|| detect_csv_delimiter(content.trim()))
+        .unwrap_or(b',');
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .quote(options.quote)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let records = reader
+        .records()
+        .collect::<Result<Vec<csv::StringRecord>, csv::Error>>()
+        .map_err(Error::Csv)?;
+
+    let (headers, rows) = split_header_row(&records, options.has_headers, |record| {
+        record.iter().map(str::to_string).collect()
+    });
+
+    if let Some(column) = options
+        .column_types
+        .keys()
+        .find(|column| !headers.contains(column))
+    {
+        return Err(Error::InvalidFormat(format!(
+            "Unknown column in column types: {}",
+            column
+        )));
+    }
+
+    // 列ごとに型を決める（指定 > 列全体からの推論 > 文字列）
+    let column_types: Vec<Option<ColumnType>> = headers
         .iter()
-        .map(|h| h.trim().to_string())
+        .enumerate()
+        .map(|(i, header)| {
+            options
+                .column_types
+                .get(header)
+                .copied()
+                .or_else(|| options.infer_types.then(|| infer_column_type(rows, i)))
+        })
         .collect();
 
-    let mut records = Vec::new();
+    let mut records = Vec::with_capacity(rows.len());
 
-    for result in reader.records() {
-        let record = result.map_err(Error::Csv)?;
+    for (row, record) in rows.iter().enumerate() {
         let mut object = serde_json::Map::new();
 
         for (i, field) in record.iter().enumerate() {
-            if let Some(header) = headers.get(i) {
-                let value = infer_value_type(field.trim());
+            if let (Some(header), Some(column_type)) = (headers.get(i), column_types.get(i)) {
+                let value = convert_csv_field(field.trim(), *column_type).ok_or_else(|| {
+                    Error::InvalidFormat(format!(
+                        "Cannot convert '{}' in column '{}' (row {}) to {}",
+                        field.trim(),
+                        header,
+                        row + 1,
+                        column_type.map_or("string", |t| t.name())
+                    ))
+                })?;
                 object.insert(header.clone(), value);
             }
         }
@@ -263,7 +925,150 @@ fn parse_csv_to_json(content: &str) -> Result<Value, Error> {
     Ok(Value::Array(records))
 }
 
-fn infer_value_type(field: &str) -> Value {
+/// スプレッドシートの行をレコードに変換（ヘッダーの扱いは CSV と同じ）
+fn spreadsheet_rows_to_json(rows: Vec<Vec<Value>>, options: &CsvOptions) -> Value {
+    // 行番号が元のシートと一致するよう、空行を除く前に skip_rows を適用
+    let rows: Vec<Vec<Value>> = rows
+        .into_iter()
+        .skip(options.skip_rows)
+        .filter(|row| row.iter().any(|cell| !cell.is_null()))
+        .collect();
+
+    let (headers, rows) = split_header_row(&rows, options.has_headers, |row| {
+        row.iter()
+            .map(|cell| match cell {
+                Value::Null => String::new(),
+                _ => value_to_string(cell),
+            })
+            .collect()
+    });
+
+    let records = rows
+        .iter()
+        .map(|row| Value::Object(headers.iter().cloned().zip(row.iter().cloned()).collect()))
+        .collect();
+
+    Value::Array(records)
+}
+
+/// 列全体の値から型を推論（空欄は無視し、混在する場合は文字列）
+fn infer_column_type(rows: &[csv::StringRecord], column: usize) -> ColumnType {
+    let mut column_type = None;
+
+    for field in rows.iter().filter_map(|record| record.get(column)) {
+        let field_type = match infer_value_type(field.trim()) {
+            Value::Null => continue,
+            Value::Bool(_) => ColumnType::Bool,
+            Value::Number(n) if n.is_i64() || n.is_u64() => ColumnType::Int,
+            Value::Number(_) => ColumnType::Float,
+            _ => return ColumnType::String,
+        };
+
+        column_type = match (column_type, field_type) {
+            (None, t) => Some(t),
+            (Some(a), b) if a == b => Some(a),
+            // 整数と小数の混在は小数として扱う
+            (Some(ColumnType::Int), ColumnType::Float)
+            | (Some(ColumnType::Float), ColumnType::Int) => Some(ColumnType::Float),
+            _ => return ColumnType::String,
+        };
+    }
+
+    column_type.unwrap_or(ColumnType::String)
+}
+
+/// 列の型に従って値を変換（None は型推論なし、変換できない場合は None）
+fn convert_csv_field(field: &str, column_type: Option<ColumnType>) -> Option<Value> {
+    let Some(column_type) = column_type else {
+        return Some(Value::String(field.to_string()));
+    };
+
+    if field.is_empty() {
+        return Some(match column_type {
+            ColumnType::String => Value::String(String::new()),
+            _ => Value::Null,
+        });
+    }
+
+    match column_type {
+        ColumnType::String => Some(Value::String(field.to_string())),
+        ColumnType::Int => field.parse::<i64>().ok().map(Value::from),
+        ColumnType::Float => field
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        ColumnType::Bool => match field.to_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(Value::Bool(true)),
+            "false" | "no" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+    }
+}
+
+/// 先頭 n 行を読み飛ばす
+fn skip_lines(content: &str, n: usize) -> &str {
+    let mut rest = content;
+    for _ in 0..n {
+        match rest.find('\n') {
+            Some(pos) => rest = &rest[pos + 1..],
+            None => return "",
+        }
+    }
+    rest
+}
+
+/// 先頭行をヘッダーとして取り出し、残りの行と分ける（--no-header の場合は col1..colN）
+fn split_header_row<T>(
+    rows: &[T],
+    has_headers: bool,
+    cells: impl Fn(&T) -> Vec<String>,
+) -> (Vec<String>, &[T]) {
+    // 行ごとに列数が異なる場合も、最も長い行の列まで読む
+    let column_count = rows.iter().map(|row| cells(row).len()).max().unwrap_or(0);
+
+    match rows.split_first() {
+        None => (Vec::new(), rows),
+        Some((first, rest)) if has_headers => {
+            // ヘッダー行より長い行の列は colN になる
+            let mut header = cells(first);
+            header.resize(column_count, String::new());
+            (normalize_headers(&header), rest)
+        }
+        Some(_) => (
+            (1..=column_count).map(|i| format!("col{}", i)).collect(),
+            rows,
+        ),
+    }
+}
+
+/// ヘッダー名を整える（空の列名は colN、重複は name_2, name_3 ...）
+fn normalize_headers(record: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    record
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            let header = header.trim();
+            let base = if header.is_empty() {
+                format!("col{}", i + 1)
+            } else {
+                header.to_string()
+            };
+
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while !seen.insert(name.clone()) {
+                name = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+pub(crate) fn infer_value_type(field: &str) -> Value {
     // 空文字チェック
     if field.is_empty() {
         return Value::Null;
@@ -276,22 +1081,35 @@ fn infer_value_type(field: &str) -> Value {
         _ => {}
     }
 
+    // 先頭が 0 の数字（郵便番号やコードなど）は文字列のまま
+    if has_leading_zero(field) {
+        return Value::String(field.to_string());
+    }
+
     // 整数判定
     if let Ok(int_val) = field.parse::<i64>() {
         return Value::Number(serde_json::Number::from(int_val));
     }
 
     // 浮動小数点数判定
-    if let Ok(float_val) = field.parse::<f64>() {
-        if let Some(num) = serde_json::Number::from_f64(float_val) {
-            return Value::Number(num);
-        }
+    if let Ok(float_val) = field.parse::<f64>()
+        && let Some(num) = serde_json::Number::from_f64(float_val)
+    {
+        return Value::Number(num);
     }
 
     // デフォルトは文字列
     Value::String(field.to_string())
 }
 
+/// "00123" のように先頭に 0 が続く数字かどうか（"0" や "0.5" は除く）
+fn has_leading_zero(field: &str) -> bool {
+    let digits = field.strip_prefix(['-', '+']).unwrap_or(field);
+    let bytes = digits.as_bytes();
+
+    bytes.len() > 1 && bytes[0] == b'0' && bytes[1].is_ascii_digit()
+}
+
 // テキスト処理用のヘルパー関数
 pub fn text_to_json_values(content: &str) -> Result<Vec<Value>, Error> {
     let lines: Vec<Value> = content
@@ -304,6 +1122,7 @@ pub fn text_to_json_values(content: &str) -> Result<Vec<Value>, Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_text_parsing() {
@@ -323,6 +1142,436 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_json_lines_detection() {
+        let jsonl = "{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2, \"name\": \"b\"}\n";
+        assert!(matches!(
+            detect_input_format(jsonl).0,
+            InputFormat::JsonLines
+        ));
+        assert_eq!(
+            parse_content(jsonl, InputFormat::JsonLines, &InputOptions::default()).unwrap(),
+            json!([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}])
+        );
+
+        // 改行を含む連結 JSON ストリーム
+        let stream = "{\n  \"id\": 1\n}\n{\n  \"id\": 2\n} {\"id\": 3}";
+        assert!(matches!(
+            detect_input_format(stream).0,
+            InputFormat::JsonLines
+        ));
+        assert_eq!(
+            parse_content(stream, InputFormat::JsonLines, &InputOptions::default()).unwrap(),
+            json!([{"id": 1}, {"id": 2}, {"id": 3}])
+        );
+
+        // 1行の JSON はカンマを含んでいても JSON として判定
+        assert!(matches!(
+            detect_input_format("{\"a\": 1, \"b\": 2}").0,
+            InputFormat::Json
+        ));
+        assert!(matches!(
+            detect_input_format("[1, 2, 3]").0,
+            InputFormat::Json
+        ));
+
+        // 壊れた行がある場合はエラー
+        assert!(
+            parse_content(
+                "{\"a\": 1}\n{\"a\": ",
+                InputFormat::JsonLines,
+                &InputOptions::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_xml_detection() {
+        let content = "<?xml version=\"1.0\"?>\n<testsuite><testcase name=\"a\"/></testsuite>";
+        assert!(matches!(detect_input_format(content).0, InputFormat::Xml));
+
+        let options = InputOptions {
+            xml_array_elements: vec!["testcase".to_string()],
+            ..InputOptions::default()
+        };
+        assert_eq!(
+            parse_content(content, InputFormat::Xml, &options).unwrap(),
+            json!({"testsuite": {"testcase": [{"@name": "a"}]}})
+        );
+
+        // 山括弧で始まるだけのテキストは XML と判定しない
+        assert!(!matches!(
+            detect_input_format("<unclosed> text\nmore <text>").0,
+            InputFormat::Xml
+        ));
+    }
+
+    #[test]
+    fn test_toml_detection() {
+        let content = r#"
+[project]
+name = "demo"
+keywords = ["a", "b"]
+released = 2024-01-15T10:00:00Z
+
+[[project.authors]]
+name = "alice"
+"#;
+        assert!(matches!(detect_input_format(content).0, InputFormat::Toml));
+        assert_eq!(
+            parse_content(content, InputFormat::Toml, &InputOptions::default()).unwrap(),
+            json!({
+                "project": {
+                    "name": "demo",
+                    "keywords": ["a", "b"],
+                    "released": "2024-01-15T10:00:00Z",
+                    "authors": [{"name": "alice"}]
+                }
+            })
+        );
+
+        // INI 風の設定ファイルは TOML と判定しない
+        assert!(!is_likely_toml("[server]\nhost = example com"));
+        assert!(matches!(
+            detect_format_from_extension(Path::new("Cargo.TOML")),
+            Some(InputFormat::Toml)
+        ));
+    }
+
+    #[test]
+    fn test_csv_delimiter_detection() {
+        assert_eq!(detect_csv_delimiter("a,b,c\n1,2,3"), Some(b','));
+        assert_eq!(detect_csv_delimiter("a\tb\tc\n1\t2\t3"), Some(b'\t'));
+        assert_eq!(
+            detect_csv_delimiter("name;price\nfoo;1,5\nbar;2,25"),
+            Some(b';')
+        );
+        assert_eq!(detect_csv_delimiter("id|name\n1|alice\n2|bob"), Some(b'|'));
+
+        // クォート内の区切り文字は数えない
+        assert_eq!(
+            detect_csv_delimiter("name;note\nfoo;\"a; b\"\nbar;c"),
+            Some(b';')
+        );
+
+        // 1行だけの TSV や列数が揃わないテキストは CSV と判定しない
+        assert_eq!(detect_csv_delimiter("plain\ttext"), None);
+        assert_eq!(detect_csv_delimiter("a | b\nplain text"), None);
+    }
+
+    #[test]
+    fn test_csv_dialects() {
+        let european = "name;price;stock\nfoo;1,5;10\nbar;2,25;0\n";
+        assert_eq!(
+            parse_csv_to_json(european, &CsvOptions::default()).unwrap(),
+            json!([
+                {"name": "foo", "price": "1,5", "stock": 10},
+                {"name": "bar", "price": "2,25", "stock": 0}
+            ])
+        );
+
+        let options = CsvOptions {
+            delimiter: Some(b'|'),
+            quote: b'\'',
+            has_headers: false,
+            ..CsvOptions::default()
+        };
+        assert_eq!(
+            parse_csv_to_json("1|'a|b'\n2|c\n", &options).unwrap(),
+            json!([
+                {"col1": 1, "col2": "a|b"},
+                {"col1": 2, "col2": "c"}
+            ])
+        );
+
+        // 列数の異なる行も最も長い行の列まで読む
+        assert_eq!(
+            parse_csv_to_json(
+                "a,b\nc,d,e\nf\n",
+                &CsvOptions {
+                    delimiter: Some(b','),
+                    has_headers: false,
+                    ..CsvOptions::default()
+                }
+            )
+            .unwrap(),
+            json!([
+                {"col1": "a", "col2": "b"},
+                {"col1": "c", "col2": "d", "col3": "e"},
+                {"col1": "f"}
+            ])
+        );
+        assert_eq!(
+            parse_csv_to_json("id,name\n1,x,extra\n", &CsvOptions::default()).unwrap(),
+            json!([{"id": 1, "name": "x", "col3": "extra"}])
+        );
+
+        let options = CsvOptions {
+            skip_rows: 2,
+            ..CsvOptions::default()
+        };
+        assert_eq!(
+            parse_csv_to_json("Exported 2024-01-01\n\nid,id,,name\n1,2,3,x\n", &options).unwrap(),
+            json!([{"id": 1, "id_2": 2, "col3": 3, "name": "x"}])
+        );
+    }
+
+    #[test]
+    fn test_csv_type_inference() {
+        let content = "zip,code,price,active,note\n00123,1e5,10,TRUE,1\n94105,A100,2.5,false,\n";
+        assert_eq!(
+            parse_csv_to_json(content, &CsvOptions::default()).unwrap(),
+            json!([
+                {"zip": "00123", "code": "1e5", "price": 10.0, "active": true, "note": 1},
+                {"zip": "94105", "code": "A100", "price": 2.5, "active": false, "note": null}
+            ])
+        );
+
+        let options = CsvOptions {
+            infer_types: false,
+            ..CsvOptions::default()
+        };
+        assert_eq!(
+            parse_csv_to_json("id,flag,empty\n1,true,\n", &options).unwrap(),
+            json!([{"id": "1", "flag": "true", "empty": ""}])
+        );
+    }
+
+    #[test]
+    fn test_csv_column_types() {
+        let options = CsvOptions {
+            column_types: HashMap::from([
+                ("zip".to_string(), ColumnType::String),
+                ("price".to_string(), ColumnType::Float),
+                ("active".to_string(), ColumnType::Bool),
+            ]),
+            ..CsvOptions::default()
+        };
+        assert_eq!(
+            parse_csv_to_json("zip,price,active,qty\n12345,3,yes,2\n", &options).unwrap(),
+            json!([{"zip": "12345", "price": 3.0, "active": true, "qty": 2}])
+        );
+
+        // 変換できない値と存在しない列はエラー
+        assert!(parse_csv_to_json("zip,price,active\n1,abc,yes\n", &options).is_err());
+        assert!(parse_csv_to_json("zip,price\n1,2\n", &options).is_err());
+
+        assert_eq!("INT".parse::<ColumnType>().unwrap(), ColumnType::Int);
+        assert!("date".parse::<ColumnType>().is_err());
+    }
+
+    #[test]
+    fn test_spreadsheet_headers() {
+        let rows = vec![
+            vec![json!("Report"), Value::Null],
+            vec![json!("id"), Value::Null, json!("id")],
+            vec![json!(1), json!(true), json!("2024-01-15")],
+        ];
+
+        let options = CsvOptions {
+            skip_rows: 1,
+            ..CsvOptions::default()
+        };
+        assert_eq!(
+            spreadsheet_rows_to_json(rows.clone(), &options),
+            json!([{"id": 1, "col2": true, "id_2": "2024-01-15"}])
+        );
+
+        let options = CsvOptions {
+            skip_rows: 2,
+            has_headers: false,
+            ..CsvOptions::default()
+        };
+        assert_eq!(
+            spreadsheet_rows_to_json(rows, &options),
+            json!([{"col1": 1, "col2": true, "col3": "2024-01-15"}])
+        );
+
+        // 空行も数えてから skip_rows を適用し、その後で空行を除く
+        let rows = vec![
+            vec![json!("Report")],
+            vec![Value::Null, Value::Null],
+            vec![json!("id"), json!("name")],
+            vec![Value::Null, Value::Null],
+            vec![json!(1), json!("alice")],
+        ];
+        let options = CsvOptions {
+            skip_rows: 2,
+            ..CsvOptions::default()
+        };
+        assert_eq!(
+            spreadsheet_rows_to_json(rows, &options),
+            json!([{"id": 1, "name": "alice"}])
+        );
+    }
+
+    #[test]
+    fn test_reject_csv_only_options() {
+        let check = |flags: &[&str]| {
+            let args = Args::try_parse_from(["hawk", "."].iter().chain(flags)).unwrap();
+            reject_csv_only_options(&args)
+        };
+
+        assert!(check(&["--sheet", "1", "--no-header", "--skip-rows", "2"]).is_ok());
+        assert!(check(&["--delimiter", ";"]).is_err());
+        assert!(check(&["--quote", "'"]).is_err());
+        assert!(check(&["--col-type", "id=string"]).is_err());
+        assert!(check(&["--schema", "types.json"]).is_err());
+    }
+
+    #[test]
+    fn test_csv_options_require_delimited_input() {
+        let options = CsvOptions {
+            skip_rows: 1,
+            ..CsvOptions::default()
+        };
+
+        assert!(check_csv_options(&InputFormat::Csv, &options).is_ok());
+        assert!(check_csv_options(&InputFormat::Tsv, &options).is_ok());
+        assert!(check_csv_options(&InputFormat::Json, &options).is_err());
+        assert!(check_csv_options(&InputFormat::Yaml, &options).is_err());
+        assert!(check_csv_options(&InputFormat::Json, &CsvOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_csv_char_option() {
+        assert_eq!(parse_csv_char("\\t", "--delimiter").unwrap(), b'\t');
+        assert_eq!(parse_csv_char("tab", "--delimiter").unwrap(), b'\t');
+        assert_eq!(parse_csv_char(";", "--delimiter").unwrap(), b';');
+        assert!(parse_csv_char("::", "--delimiter").is_err());
+    }
+
+    #[test]
+    fn test_resolve_output_format() {
+        let resolve = |flags: &[&str]| {
+            let args = Args::try_parse_from(["hawk", "."].iter().chain(flags)).unwrap();
+            resolve_output_format(&args)
+        };
+
+        assert!(matches!(resolve(&[]), Ok(OutputFormat::Auto)));
+        assert!(matches!(
+            resolve(&["--format", "json"]),
+            Ok(OutputFormat::Json {
+                indent: Some(2),
+                raw: false
+            })
+        ));
+        assert!(matches!(
+            resolve(&["-c"]),
+            Ok(OutputFormat::Json {
+                indent: None,
+                raw: false
+            })
+        ));
+        assert!(matches!(
+            resolve(&["--indent", "4", "-r"]),
+            Ok(OutputFormat::Json {
+                indent: Some(4),
+                raw: true
+            })
+        ));
+        assert!(matches!(
+            resolve(&["--format", "jsonl", "-r", "-c"]),
+            Ok(OutputFormat::JsonLines { raw: true })
+        ));
+
+        assert!(resolve(&["--format", "jsonl", "--indent", "2"]).is_err());
+        assert!(resolve(&["--format", "csv", "-r"]).is_err());
+        assert!(resolve(&["--yaml-documents"]).is_err());
+    }
+
+    #[test]
+    fn test_yaml_document_stream() {
+        let options = InputOptions::default();
+
+        // 値やブロックスカラー内の "---" はドキュメントの区切りではない
+        let content = "---\nkind: Note\ntitle: a --- b\nbody: |\n  intro\n  ---\n  outro\n---\nkind: Service\n---\n- 1\n- 2\n---\n";
+        assert_eq!(
+            parse_content(content, InputFormat::Yaml, &options).unwrap(),
+            json!([
+                {"kind": "Note", "title": "a --- b", "body": "intro\n---\noutro\n", "document_index": 0},
+                {"kind": "Service", "document_index": 1},
+                [1, 2]
+            ])
+        );
+
+        // 位置は null のドキュメントを含めたストリーム内の位置
+        assert_eq!(
+            parse_yaml_documents("---\n~\n---\na: 1\n---\nb: 2\n").unwrap(),
+            (
+                json!([{"a": 1, "document_index": 1}, {"b": 2, "document_index": 2}]),
+                true
+            )
+        );
+
+        // 既に document_index を持つドキュメントは上書きしない
+        assert_eq!(
+            parse_yaml_documents("document_index: 99\n---\na: 1\n").unwrap(),
+            (json!([{"document_index": 99}, {"a": 1}]), false)
+        );
+
+        // 1つだけのドキュメントはそのまま
+        assert_eq!(
+            parse_content("---\nname: demo\n", InputFormat::Yaml, &options).unwrap(),
+            json!({"name": "demo"})
+        );
+        assert!(parse_content("a: 1\n---\nb: [", InputFormat::Yaml, &options).is_err());
+    }
+
+    #[test]
+    fn test_yaml_with_commas_detection() {
+        // 値にカンマを含む YAML は CSV にも見えるが、YAML を優先して通知する
+        let content = "tags: a, b\nauthors: alice, bob\nsummary: fast, small, simple";
+        let (format, notice) = detect_input_format(content);
+        assert!(matches!(format, InputFormat::Yaml));
+        assert!(notice.is_some());
+
+        // 曖昧でない CSV には通知しない
+        let (format, notice) = detect_input_format("id,name\n1,alice\n2,bob");
+        assert!(matches!(format, InputFormat::Csv));
+        assert!(notice.is_none());
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        let detect = |path: &str| detect_format_from_extension(Path::new(path));
+
+        assert!(matches!(detect("data.json"), Some(InputFormat::Json)));
+        assert!(matches!(
+            detect("events.NDJSON"),
+            Some(InputFormat::JsonLines)
+        ));
+        assert!(matches!(detect("config.yml"), Some(InputFormat::Yaml)));
+        assert!(matches!(detect("table.tsv"), Some(InputFormat::Tsv)));
+        assert!(matches!(detect("notes.txt"), Some(InputFormat::Text)));
+        assert!(matches!(detect("data.json.gz"), Some(InputFormat::Json)));
+        assert!(matches!(detect("rows.csv.zst"), Some(InputFormat::Csv)));
+
+        // 形式が決まらない拡張子は内容から判定
+        assert!(detect("app.log").is_none());
+        assert!(detect("app.log.gz").is_none());
+        assert!(detect("README").is_none());
+    }
+
+    #[test]
+    fn test_explicit_json_and_tsv() {
+        let options = InputOptions::default();
+
+        // .json という名前の JSON Lines も読める
+        assert_eq!(
+            parse_content("{\"a\": 1}\n{\"a\": 2}", InputFormat::Json, &options).unwrap(),
+            json!([{"a": 1}, {"a": 2}])
+        );
+        assert!(parse_content("{\"a\": ", InputFormat::Json, &options).is_err());
+
+        // TSV は値にカンマを含んでいてもタブで区切る
+        assert_eq!(
+            parse_content("name\tnote\nalice\ta, b", InputFormat::Tsv, &options).unwrap(),
+            json!([{"name": "alice", "note": "a, b"}])
+        );
+    }
+
     #[test]
     fn test_yaml_detection() {
         use super::{is_structured_yaml, is_valid_yaml_line};
@@ -340,6 +1589,14 @@ mod tests {
             "ServerName: localhost\nServerPort: 8080"
         )); // 設定ファイル風だがYAMLではない
 
+        // マーカーは行頭のキーとしてのみ認識する
+        let release_notes = "Release notes\nThis release bumps the schema version: see below.\nNothing else changed, really.";
+        assert!(!is_structured_yaml(release_notes));
+        assert!(matches!(
+            detect_input_format(release_notes).0,
+            InputFormat::Text
+        ));
+
         // 個別行のテスト
         assert!(is_valid_yaml_line("key: value"));
         assert!(is_valid_yaml_line("  nested: item"));
@@ -0,0 +1,259 @@
+use std::io::{Cursor, Read};
+
+use calamine::{Data, Range, Reader, Sheets, open_workbook_auto_from_rs};
+use chrono::Timelike;
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::Error;
+
+/// xlsx / xlsb / ods（zip 形式）のマジックナンバー
+pub const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// xls（OLE 形式）のマジックナンバー
+const OLE_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// ods の mimetype（テンプレートの "-template" 付きも含む）
+const ODS_MIMETYPE: &str = "application/vnd.oasis.opendocument.spreadsheet";
+
+/// [Content_Types].xml に含まれる xlsx / xlsm / xlsb のブック本体のコンテンツタイプ
+const OOXML_WORKBOOK_CONTENT_TYPES: [&str; 5] = [
+    "spreadsheetml.sheet.main+xml",
+    "spreadsheetml.template.main+xml",
+    "ms-excel.sheet.macroEnabled.main+xml",
+    "ms-excel.template.macroEnabled.main+xml",
+    "ms-excel.sheet.binary.macroEnabled.main",
+];
+
+/// スプレッドシートか判定
+///
+/// zip 形式は docx や jar なども同じマジックナンバーなので、ブックのコンテンツタイプ
+/// （ods は mimetype）まで確認する。
+pub fn is_spreadsheet(bytes: &[u8]) -> bool {
+    bytes.starts_with(OLE_MAGIC) || (bytes.starts_with(ZIP_MAGIC) && is_workbook_archive(bytes))
+}
+
+fn is_workbook_archive(bytes: &[u8]) -> bool {
+    let Ok(mut archive) = ZipArchive::new(Cursor::new(bytes)) else {
+        return false;
+    };
+
+    if let Some(mimetype) = read_archive_entry(&mut archive, "mimetype") {
+        return mimetype.trim().starts_with(ODS_MIMETYPE);
+    }
+
+    read_archive_entry(&mut archive, "[Content_Types].xml").is_some_and(|content_types| {
+        OOXML_WORKBOOK_CONTENT_TYPES
+            .iter()
+            .any(|content_type| content_types.contains(content_type))
+    })
+}
+
+fn read_archive_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// シートを読み込み、セルの型を保った行の配列を返す
+///
+/// `sheet` はシート名か 0 始まりの番号（名前を優先）。省略時は最初のシート。空行もそのまま返す。
+pub fn read_spreadsheet(bytes: &[u8], sheet: Option<&str>) -> Result<Vec<Vec<Value>>, Error> {
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(bytes))?;
+    let range = select_sheet(&mut workbook, sheet)?;
+
+    let rows = range
+        .rows()
+        .map(|row| row.iter().map(cell_to_value).collect())
+        .collect();
+
+    Ok(rows)
+}
+
+fn select_sheet(
+    workbook: &mut Sheets<Cursor<&[u8]>>,
+    sheet: Option<&str>,
+) -> Result<Range<Data>, Error> {
+    let sheet_names = workbook.sheet_names();
+
+    let name = resolve_sheet_name(&sheet_names, sheet)
+        .cloned()
+        .ok_or_else(|| {
+            Error::InvalidFormat(format!(
+                "Sheet not found: {}. Available sheets: {}",
+                sheet.unwrap_or("(first)"),
+                sheet_names.join(", ")
+            ))
+        })?;
+
+    Ok(workbook.worksheet_range(&name)?)
+}
+
+/// シート名を優先し、一致する名前がなければ 0 始まりの番号として解釈
+fn resolve_sheet_name<'a>(sheet_names: &'a [String], sheet: Option<&str>) -> Option<&'a String> {
+    let Some(sheet) = sheet else {
+        return sheet_names.first();
+    };
+
+    sheet_names
+        .iter()
+        .find(|name| name.as_str() == sheet)
+        .or_else(|| {
+            sheet
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| sheet_names.get(index))
+        })
+}
+
+/// セルの値を JSON に変換（日時は ISO 8601 文字列、期間は秒数）
+fn cell_to_value(cell: &Data) -> Value {
+    match cell {
+        Data::Empty => Value::Null,
+        Data::String(s) => Value::String(s.clone()),
+        Data::Int(i) => Value::from(*i),
+        Data::Float(f) => float_to_value(*f),
+        Data::Bool(b) => Value::Bool(*b),
+        Data::DateTime(datetime) if datetime.is_duration() => datetime
+            .as_duration()
+            .map(|duration| float_to_value(duration.num_milliseconds() as f64 / 1000.0))
+            .unwrap_or(Value::Null),
+        Data::DateTime(datetime) => datetime
+            .as_datetime()
+            .map(|datetime| {
+                // 時刻を持たないセルは日付のみ
+                if datetime.time().num_seconds_from_midnight() == 0 && datetime.nanosecond() == 0 {
+                    datetime.format("%Y-%m-%d").to_string()
+                } else {
+                    datetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+                }
+            })
+            .map(Value::String)
+            .unwrap_or(Value::Null),
+        Data::DateTimeIso(s) | Data::DurationIso(s) => Value::String(s.clone()),
+        Data::Error(error) => Value::String(error.to_string()),
+    }
+}
+
+/// Excel は数値を浮動小数点で持つため、整数になる値は整数として扱う
+fn float_to_value(number: f64) -> Value {
+    if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+        return Value::from(number as i64);
+    }
+
+    serde_json::Number::from_f64(number)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ORDERS_XLSX: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/small/orders.xlsx"
+    ));
+
+    #[test]
+    fn test_read_xlsx_cell_types() {
+        assert!(is_spreadsheet(ORDERS_XLSX));
+
+        let rows = read_spreadsheet(ORDERS_XLSX, None).unwrap();
+        assert_eq!(
+            rows[0],
+            vec![
+                json!("order_id"),
+                json!("quantity"),
+                json!("price"),
+                json!("paid"),
+                json!("order_date")
+            ]
+        );
+        assert_eq!(
+            rows[1],
+            vec![
+                json!("ORD001"),
+                json!(2),
+                json!(299.99),
+                json!(true),
+                json!("2024-01-15")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_ods() {
+        let ods: &[u8] = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/examples/small/orders.ods"
+        ));
+        assert!(is_spreadsheet(ods));
+
+        let rows = read_spreadsheet(ods, None).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[1],
+            vec![
+                json!("ORD001"),
+                json!(2),
+                json!(299.99),
+                json!(true),
+                json!("2024-01-15")
+            ]
+        );
+        assert_eq!(rows, read_spreadsheet(ORDERS_XLSX, None).unwrap());
+    }
+
+    #[test]
+    fn test_other_zip_archives_are_not_spreadsheets() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let archive = |entries: &[(&str, &str)]| {
+            let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+            for (name, content) in entries {
+                writer
+                    .start_file(*name, SimpleFileOptions::default())
+                    .unwrap();
+                writer.write_all(content.as_bytes()).unwrap();
+            }
+            writer.finish().unwrap().into_inner()
+        };
+
+        let plain = archive(&[("data.csv", "a,b\n1,2\n")]);
+        assert!(plain.starts_with(ZIP_MAGIC));
+        assert!(!is_spreadsheet(&plain));
+
+        // docx も [Content_Types].xml を持つがブックではない
+        let docx = archive(&[(
+            "[Content_Types].xml",
+            r#"<Types><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/></Types>"#,
+        )]);
+        assert!(!is_spreadsheet(&docx));
+
+        let odt = archive(&[("mimetype", "application/vnd.oasis.opendocument.text")]);
+        assert!(!is_spreadsheet(&odt));
+    }
+
+    #[test]
+    fn test_select_sheet() {
+        let by_name = read_spreadsheet(ORDERS_XLSX, Some("Summary")).unwrap();
+        let by_index = read_spreadsheet(ORDERS_XLSX, Some("1")).unwrap();
+        assert_eq!(by_name, by_index);
+        assert_eq!(by_name[1], vec![json!("total"), json!(1199.97)]);
+
+        assert!(read_spreadsheet(ORDERS_XLSX, Some("Missing")).is_err());
+        assert!(read_spreadsheet(ORDERS_XLSX, Some("5")).is_err());
+
+        // 数字だけのシート名は番号より名前を優先
+        let names = vec!["2023".to_string(), "2024".to_string(), "0".to_string()];
+        assert_eq!(resolve_sheet_name(&names, Some("2024")), Some(&names[1]));
+        assert_eq!(resolve_sheet_name(&names, Some("0")), Some(&names[2]));
+        assert_eq!(resolve_sheet_name(&names, Some("1")), Some(&names[1]));
+        assert_eq!(resolve_sheet_name(&names, None), Some(&names[0]));
+        assert_eq!(resolve_sheet_name(&names, Some("7")), None);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::Error;
+use crate::filter::extract_sort_key;
+use crate::{Error, normalize_datetime_sort_keys, value_to_string};
 use serde_json::Value;
 
 /// 統計操作を適用する
@@ -47,27 +48,76 @@ fn apply_unique(data: &[Value], field: Option<&str>) -> Result<Value, Error> {
     Ok(Value::Array(result))
 }
 
-/// ソート
-fn apply_sort(data: &[Value], field: Option<&str>) -> Result<Value, Error> {
-    let mut sorted_data = data.to_vec();
+/// レコード全体の重複除去（指定フィールドの組み合わせをキーとする）
+pub fn apply_distinct_by(data: Vec<Value>, fields: &[String], keep_last: bool) -> Vec<Value> {
+    use std::collections::HashSet;
 
-    sorted_data.sort_by(|a, b| {
-        let val_a = if let Some(field_name) = field {
-            a.get(field_name).unwrap_or(&Value::Null)
-        } else {
-            a
-        };
+    let mut seen = HashSet::new();
+
+    if keep_last {
+        // 後ろから走査して最後の出現を残す（元の順序は維持）
+        let mut result: Vec<Value> = data
+            .into_iter()
+            .rev()
+            .filter(|item| seen.insert(record_key(item, fields)))
+            .collect();
+        result.reverse();
+        result
+    } else {
+        data.into_iter()
+            .filter(|item| seen.insert(record_key(item, fields)))
+            .collect()
+    }
+}
 
-        let val_b = if let Some(field_name) = field {
-            b.get(field_name).unwrap_or(&Value::Null)
-        } else {
-            b
-        };
+/// キーが複数回出現するレコードのみを抽出
+pub fn apply_duplicates(data: Vec<Value>, fields: &[String]) -> Vec<Value> {
+    use std::collections::HashMap;
+
+    let mut counts: HashMap<Vec<Option<String>>, usize> = HashMap::new();
+    for item in &data {
+        *counts.entry(record_key(item, fields)).or_default() += 1;
+    }
+
+    data.into_iter()
+        .filter(|item| counts[&record_key(item, fields)] > 1)
+        .collect()
+}
 
-        compare_json_values(val_a, val_b)
-    });
+/// 重複判定用のキーを作成（`user.id` のようなネストしたパスに対応。1 と "1"、1 と 1.0 は同じキーになる）
+fn record_key(item: &Value, fields: &[String]) -> Vec<Option<String>> {
+    fields
+        .iter()
+        .map(|field| match extract_sort_key(item, field) {
+            Value::Null => None,
+            Value::Number(n) if n.is_f64() => n.as_f64().map(|f| f.to_string()),
+            value => Some(value_to_string(&value)),
+        })
+        .collect()
+}
 
-    Ok(Value::Array(sorted_data))
+/// ソート
+fn apply_sort(data: &[Value], field: Option<&str>) -> Result<Value, Error> {
+    let mut decorated: Vec<(Value, &Value)> = data
+        .iter()
+        .map(|item| {
+            let key = match field {
+                Some(field_name) => item.get(field_name).cloned().unwrap_or(Value::Null),
+                None => item.clone(),
+            };
+            (key, item)
+        })
+        .collect();
+    normalize_datetime_sort_keys(decorated.iter_mut().map(|(key, _)| key));
+
+    decorated.sort_by(|(key_a, _), (key_b, _)| compare_json_values(key_a, key_b));
+
+    Ok(Value::Array(
+        decorated
+            .into_iter()
+            .map(|(_, item)| item.clone())
+            .collect(),
+    ))
 }
 
 /// 中央値を計算
@@ -138,6 +188,7 @@ fn compare_json_values(a: &Value, b: &Value) -> std::cmp::Ordering {
             .unwrap_or(0.0)
             .partial_cmp(&n2.as_f64().unwrap_or(0.0))
             .unwrap_or(Ordering::Equal),
+        // 日時の列は normalize_datetime_sort_keys で時系列順の文字列になっている
         (Value::String(s1), Value::String(s2)) => s1.cmp(s2),
         (Value::Bool(b1), Value::Bool(b2)) => b1.cmp(b2),
         (Value::Null, Value::Null) => Ordering::Equal,
@@ -163,6 +214,7 @@ fn get_type_priority(value: &Value) -> u8 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_unique_operation() {
@@ -201,6 +253,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sort_datetime_strings() {
+        let data = vec![
+            json!("2024-03-14T10:00:00+09:00"),
+            json!("2024-03-14T02:30:00Z"),
+            json!("2024-03-13 23:00:00"),
+        ];
+        assert_eq!(
+            apply_sort(&data, None).unwrap(),
+            json!([
+                "2024-03-13 23:00:00",
+                "2024-03-14T10:00:00+09:00",
+                "2024-03-14T02:30:00Z"
+            ])
+        );
+
+        // 日時でない文字列が混ざる場合は辞書順
+        let data = vec![
+            json!("2024-03-14T02:30:00Z"),
+            json!("pending"),
+            json!("2024-03-13"),
+        ];
+        assert_eq!(
+            apply_sort(&data, None).unwrap(),
+            json!(["2024-03-13", "2024-03-14T02:30:00Z", "pending"])
+        );
+    }
+
     #[test]
     fn test_median_even() {
         let data = vec![
@@ -237,6 +317,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_distinct_by_keeps_whole_records() {
+        let data = vec![
+            serde_json::json!({"email": "a@x.com", "id": 1}),
+            serde_json::json!({"email": "b@x.com", "id": 2}),
+            serde_json::json!({"email": "a@x.com", "id": 3}),
+        ];
+        let fields = vec!["email".to_string()];
+
+        let result = apply_distinct_by(data.clone(), &fields, false);
+        assert_eq!(result, vec![data[0].clone(), data[1].clone()]);
+
+        let result = apply_distinct_by(data.clone(), &fields, true);
+        assert_eq!(result, vec![data[1].clone(), data[2].clone()]);
+    }
+
+    #[test]
+    fn test_distinct_by_multiple_fields_normalizes_numbers() {
+        let data = vec![
+            serde_json::json!({"user_id": 1, "day": "mon"}),
+            serde_json::json!({"user_id": "1", "day": "mon"}),
+            serde_json::json!({"user_id": 1.0, "day": "tue"}),
+            serde_json::json!({"user_id": null, "day": "tue"}),
+            serde_json::json!({"user_id": "null", "day": "tue"}),
+        ];
+        let fields = vec!["user_id".to_string(), "day".to_string()];
+
+        let result = apply_distinct_by(data.clone(), &fields, false);
+        assert_eq!(
+            result,
+            vec![
+                data[0].clone(),
+                data[2].clone(),
+                data[3].clone(),
+                data[4].clone()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let data = vec![
+            serde_json::json!({"id": 1, "v": "a"}),
+            serde_json::json!({"id": 2, "v": "b"}),
+            serde_json::json!({"id": 1, "v": "c"}),
+        ];
+
+        let result = apply_duplicates(data.clone(), &["id".to_string()]);
+        assert_eq!(result, vec![data[0].clone(), data[2].clone()]);
+    }
+
     #[test]
     fn test_unique_with_field() {
         let data = vec![
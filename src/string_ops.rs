@@ -1,6 +1,13 @@
-use crate::Error;
+use crate::{
+    Error, GrokPattern, apply_datetime_operation, apply_numeric_operation, display_width,
+    extract_parse_pattern, is_datetime_operation, is_numeric_operation, parse_key_values,
+    parse_kv_separators,
+};
 use serde_json::Value;
 
+/// repeat やパディングで生成する文字列の上限（バイト数・表示幅）
+const MAX_GENERATED_STRING_LENGTH: usize = 16 * 1024 * 1024;
+
 /// 文字列操作を適用する
 pub fn apply_string_operation(value: &Value, operation: &str) -> Result<Value, Error> {
     match operation {
@@ -116,6 +123,96 @@ pub fn apply_string_operation(value: &Value, operation: &str) -> Result<Value, E
             // join操作は配列に対して適用
             apply_join_operation(value, op)
         }
+        // 表示幅を考慮したパディング・切り詰め
+        "width" => {
+            let text = extract_text_value(value)?;
+            Ok(Value::Number(display_width(&text).into()))
+        }
+        op if op.starts_with("pad_left(") && op.ends_with(")") => {
+            let text = extract_text_value(value)?;
+            let (width, fill) = extract_width_arguments(op, " ")?;
+            let padding = build_padding(&fill, width.saturating_sub(display_width(&text)));
+            Ok(Value::String(format!("{}{}", padding, text)))
+        }
+        op if op.starts_with("pad_right(") && op.ends_with(")") => {
+            let text = extract_text_value(value)?;
+            let (width, fill) = extract_width_arguments(op, " ")?;
+            let padding = build_padding(&fill, width.saturating_sub(display_width(&text)));
+            Ok(Value::String(format!("{}{}", text, padding)))
+        }
+        op if op.starts_with("center(") && op.ends_with(")") => {
+            let text = extract_text_value(value)?;
+            let (width, fill) = extract_width_arguments(op, " ")?;
+            let total = width.saturating_sub(display_width(&text));
+            // 余りは右側に寄せる
+            let left = build_padding(&fill, total / 2);
+            let right = build_padding(&fill, total - display_width(&left));
+            Ok(Value::String(format!("{}{}{}", left, text, right)))
+        }
+        op if op.starts_with("truncate(") && op.ends_with(")") => {
+            let text = extract_text_value(value)?;
+            let (width, ellipsis) = extract_width_arguments(op, "")?;
+            Ok(Value::String(truncate_to_width(&text, width, &ellipsis)))
+        }
+        op if op.starts_with("repeat(") && op.ends_with(")") => {
+            let text = extract_text_value(value)?;
+            let count = extract_string_argument(op)?
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| Error::StringOperation("Invalid count for repeat".to_string()))?;
+            text.len()
+                .checked_mul(count)
+                .filter(|&length| length <= MAX_GENERATED_STRING_LENGTH)
+                .ok_or_else(|| {
+                    Error::StringOperation(format!(
+                        "repeat result would exceed {} bytes",
+                        MAX_GENERATED_STRING_LENGTH
+                    ))
+                })?;
+            Ok(Value::String(text.repeat(count)))
+        }
+        // grokパターンで構造化（マッチしなければnull）
+        op if op.starts_with("parse(") && op.ends_with(")") => {
+            let string_val = extract_string_value(value)?;
+            let pattern = extract_parse_pattern(&op[6..op.len() - 1])?;
+            Ok(GrokPattern::cached(&pattern)?
+                .parse_line(string_val)
+                .unwrap_or(Value::Null))
+        }
+        // key=value 形式を構造化
+        op if op == "logfmt" || op == "kv" || (op.starts_with("kv(") && op.ends_with(")")) => {
+            let string_val = extract_string_value(value)?;
+            let (pair_separator, kv_separator) = parse_kv_separators(op)?;
+            Ok(parse_key_values(
+                string_val,
+                pair_separator.as_deref(),
+                &kv_separator,
+            ))
+        }
+        // 型変換
+        "type" => Ok(Value::String(get_type_name(value).to_string())),
+        "tostring" => Ok(Value::String(match value {
+            Value::String(s) => s.clone(),
+            _ => value.to_string(),
+        })),
+        "tojson" => Ok(Value::String(value.to_string())),
+        "tobool" => convert_to_bool(value),
+        "tonumber" => convert_to_number(value, false),
+        op if op.starts_with("tonumber(") && op.ends_with(")") => {
+            let on_error = extract_string_argument(op)?;
+            match on_error.as_str() {
+                "error" => convert_to_number(value, false),
+                "null" => convert_to_number(value, true),
+                _ => Err(Error::StringOperation(format!(
+                    "Invalid tonumber policy: '{}'. Use \"error\" or \"null\"",
+                    on_error
+                ))),
+            }
+        }
+        // 日時操作（to_datetime, strftime, date_trunc など）
+        op if is_datetime_operation(op) => apply_datetime_operation(value, op),
+        // 数値操作（round, floor, clamp など）
+        op if is_numeric_operation(op) => apply_numeric_operation(value, op),
         _ => Err(Error::StringOperation(format!(
             "Unknown string operation: {}",
             operation
@@ -173,6 +270,80 @@ fn extract_string_value(value: &Value) -> Result<&str, Error> {
     }
 }
 
+/// 文字列または数値をテキストとして取得（パディング・repeat 用）
+fn extract_text_value(value: &Value) -> Result<String, Error> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        _ => Err(Error::StringOperation(format!(
+            "Padding and repeat operations can only be applied to strings or numbers, got: {}",
+            get_type_name(value)
+        ))),
+    }
+}
+
+/// 幅と補助文字列の引数を抽出（例: pad_left(10, "0") → (10, "0")）
+fn extract_width_arguments(operation: &str, default: &str) -> Result<(usize, String), Error> {
+    let args = parse_operation_arguments(operation)?;
+
+    let width = args
+        .first()
+        .and_then(|arg| arg.parse::<usize>().ok())
+        .ok_or_else(|| Error::StringOperation(format!("Invalid width: {}", operation)))?;
+
+    if width > MAX_GENERATED_STRING_LENGTH {
+        return Err(Error::StringOperation(format!(
+            "Width must be at most {}: {}",
+            MAX_GENERATED_STRING_LENGTH, operation
+        )));
+    }
+
+    match args.len() {
+        1 => Ok((width, default.to_string())),
+        2 => Ok((width, args[1].clone())),
+        _ => Err(Error::StringOperation(format!(
+            "Too many arguments: {}",
+            operation
+        ))),
+    }
+}
+
+/// 指定した表示幅に収まるまで fill を繰り返す（全角の fill ははみ出さない範囲で）
+fn build_padding(fill: &str, width: usize) -> String {
+    let fill_width = display_width(fill);
+    if fill_width == 0 {
+        return " ".repeat(width);
+    }
+
+    let mut padding = fill.repeat(width / fill_width);
+    // 端数は空白で埋める
+    padding.push_str(&" ".repeat(width % fill_width));
+    padding
+}
+
+/// 表示幅で切り詰め、省略記号を含めて width 以内に収める
+fn truncate_to_width(text: &str, width: usize, ellipsis: &str) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+
+    let budget = width.saturating_sub(display_width(ellipsis));
+    let mut result = String::new();
+    let mut used = 0;
+
+    for ch in text.chars() {
+        let ch_width = display_width(ch.encode_utf8(&mut [0; 4]));
+        if used + ch_width > budget {
+            break;
+        }
+        used += ch_width;
+        result.push(ch);
+    }
+
+    result.push_str(ellipsis);
+    result
+}
+
 /// join操作を配列に適用
 fn apply_join_operation(value: &Value, operation: &str) -> Result<Value, Error> {
     if let Value::Array(arr) = value {
@@ -300,10 +471,50 @@ fn extract_substring_arguments(operation: &str) -> Result<(usize, Option<usize>)
     Ok((start, length))
 }
 
+/// 括弧内の引数をカンマで分割（引用符内のカンマや空白はそのまま残す）
+pub(crate) fn parse_operation_arguments(operation: &str) -> Result<Vec<String>, Error> {
+    let start_pos = operation
+        .find('(')
+        .ok_or_else(|| Error::StringOperation("Missing opening parenthesis".to_string()))?
+        + 1;
+    let end_pos = operation
+        .rfind(')')
+        .filter(|pos| *pos >= start_pos)
+        .ok_or_else(|| Error::StringOperation("Missing closing parenthesis".to_string()))?;
+
+    let inner = &operation[start_pos..end_pos];
+    if inner.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut raw_args = Vec::new();
+    let mut arg_start = 0;
+    let mut quote: Option<char> = None;
+
+    for (pos, ch) in inner.char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(q), c) if c == q => quote = None,
+            (None, ',') => {
+                raw_args.push(&inner[arg_start..pos]);
+                arg_start = pos + 1;
+            }
+            _ => {}
+        }
+    }
+    raw_args.push(&inner[arg_start..]);
+
+    raw_args
+        .into_iter()
+        .map(|arg| clean_string_argument(arg.trim()))
+        .collect()
+}
+
 /// 引用符をクリーニング
 fn clean_string_argument(arg: &str) -> Result<String, Error> {
-    let cleaned = if (arg.starts_with('"') && arg.ends_with('"'))
-        || (arg.starts_with('\'') && arg.ends_with('\''))
+    let cleaned = if arg.len() >= 2
+        && ((arg.starts_with('"') && arg.ends_with('"'))
+            || (arg.starts_with('\'') && arg.ends_with('\'')))
     {
         &arg[1..arg.len() - 1]
     } else {
@@ -535,6 +746,58 @@ fn apply_slice_to_string_array(
     array[start_idx..end_idx].to_vec()
 }
 
+/// 数値に変換（null_on_error が true の場合は変換できない値を null にする）
+fn convert_to_number(value: &Value, null_on_error: bool) -> Result<Value, Error> {
+    let converted = match value {
+        Value::Number(_) | Value::Null => Some(value.clone()),
+        Value::String(s) => {
+            let trimmed = s.trim();
+            if let Ok(int_val) = trimmed.parse::<i64>() {
+                Some(Value::Number(int_val.into()))
+            } else {
+                trimmed
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+            }
+        }
+        _ => None,
+    };
+
+    match converted {
+        Some(number) => Ok(number),
+        None if null_on_error => Ok(Value::Null),
+        None => Err(Error::StringOperation(format!(
+            "Cannot convert {} to number: {}",
+            get_type_name(value),
+            value
+        ))),
+    }
+}
+
+/// 真偽値に変換（true/false, yes/no, on/off, 1/0 を受け付ける）
+fn convert_to_bool(value: &Value) -> Result<Value, Error> {
+    let converted = match value {
+        Value::Bool(_) | Value::Null => Some(value.clone()),
+        Value::Number(n) => Some(Value::Bool(n.as_f64().is_some_and(|f| f != 0.0))),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "yes" | "y" | "on" | "1" => Some(Value::Bool(true)),
+            "false" | "no" | "n" | "off" | "0" | "" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    converted.ok_or_else(|| {
+        Error::StringOperation(format!(
+            "Cannot convert {} to boolean: {}",
+            get_type_name(value),
+            value
+        ))
+    })
+}
+
 /// contains のOR条件処理
 fn apply_contains_or_condition(text: &str, pattern: &str) -> Result<Value, Error> {
     let patterns: Vec<&str> = pattern.split('|').map(|p| p.trim()).collect();
@@ -669,4 +932,82 @@ mod tests {
         let result = apply_string_operation(&value, "upper");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_type_conversion_operations() {
+        let value = Value::String(" 42 ".to_string());
+        let result = apply_string_pipeline(&value, &["tonumber", "tostring", "length"]).unwrap();
+        assert_eq!(result, Value::Number(2.into()));
+
+        let result = apply_string_operation(&Value::String("1.5".to_string()), "tonumber").unwrap();
+        assert_eq!(result, serde_json::json!(1.5));
+
+        let invalid = Value::String("n/a".to_string());
+        assert!(apply_string_operation(&invalid, "tonumber").is_err());
+        let result = apply_string_operation(&invalid, r#"tonumber("null")"#).unwrap();
+        assert_eq!(result, Value::Null);
+
+        let result = apply_string_operation(&Value::String("Yes".to_string()), "tobool").unwrap();
+        assert_eq!(result, Value::Bool(true));
+        assert!(apply_string_operation(&invalid, "tobool").is_err());
+
+        let object = serde_json::json!({"a": [1, "x"]});
+        let result = apply_string_operation(&object, "tojson").unwrap();
+        assert_eq!(result, Value::String(r#"{"a":[1,"x"]}"#.to_string()));
+        let result = apply_string_operation(&object, "type").unwrap();
+        assert_eq!(result, Value::String("object".to_string()));
+    }
+
+    #[test]
+    fn test_padding_and_truncation() {
+        let value = Value::String("42".to_string());
+        let result = apply_string_operation(&value, r#"pad_left(5, "0")"#).unwrap();
+        assert_eq!(result, Value::String("00042".to_string()));
+
+        let result = apply_string_operation(&Value::Number(7.into()), "pad_right(3)").unwrap();
+        assert_eq!(result, Value::String("7  ".to_string()));
+
+        let result = apply_string_operation(&value, r#"center(6, "*")"#).unwrap();
+        assert_eq!(result, Value::String("**42**".to_string()));
+
+        let value = Value::String("ab".to_string());
+        let result = apply_string_operation(&value, "repeat(3)").unwrap();
+        assert_eq!(result, Value::String("ababab".to_string()));
+
+        let result = apply_string_operation(&Value::Number(7.into()), "repeat(2)").unwrap();
+        assert_eq!(result, Value::String("77".to_string()));
+
+        // 巨大な結果はメモリ確保の前にエラーにする
+        assert!(apply_string_operation(&value, "repeat(99999999999999999)").is_err());
+        assert!(apply_string_operation(&value, "pad_left(99999999999999999)").is_err());
+        assert!(apply_string_operation(&value, r#"center(99999999999999999, "=")"#).is_err());
+    }
+
+    #[test]
+    fn test_display_width_operations() {
+        // 全角文字は幅2として扱う
+        let value = Value::String("東京".to_string());
+        let result = apply_string_operation(&value, "width").unwrap();
+        assert_eq!(result, Value::Number(4.into()));
+
+        let result = apply_string_operation(&value, "pad_right(6)").unwrap();
+        assert_eq!(result, Value::String("東京  ".to_string()));
+
+        let value = Value::String("こんにちは世界".to_string());
+        let result = apply_string_operation(&value, r#"truncate(9, "…")"#).unwrap();
+        assert_eq!(result, Value::String("こんにち…".to_string()));
+
+        let value = Value::String("hello, world".to_string());
+        let result = apply_string_operation(&value, "truncate(5)").unwrap();
+        assert_eq!(result, Value::String("hello".to_string()));
+    }
+
+    #[test]
+    fn test_clean_string_argument_short_quotes() {
+        // 引用符1文字だけの引数は外さずにそのまま使う
+        assert_eq!(clean_string_argument("'").unwrap(), "'");
+        assert_eq!(clean_string_argument("\"").unwrap(), "\"");
+        assert_eq!(clean_string_argument("''").unwrap(), "");
+        assert_eq!(clean_string_argument("'ab'").unwrap(), "ab");
+    }
 }
@@ -1,4 +1,5 @@
 use serde_json::Value;
+use unicode_width::UnicodeWidthStr;
 
 pub fn value_to_string(value: &Value) -> String {
     match value {
@@ -7,6 +8,17 @@ pub fn value_to_string(value: &Value) -> String {
     }
 }
 
+/// 端末上の表示幅（全角文字・絵文字は幅2）
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// 表示幅が width になるまで右側を空白で埋める
+pub fn pad_display_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}", text, " ".repeat(padding))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,4 +49,12 @@ mod tests {
         let value = Value::Null;
         assert_eq!(value_to_string(&value), "null");
     }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(pad_display_width("日本", 6), "日本  ");
+        assert_eq!(pad_display_width("too long", 3), "too long");
+    }
 }
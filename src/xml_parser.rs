@@ -0,0 +1,246 @@
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use serde_json::{Map, Value};
+
+use crate::Error;
+
+/// 属性名の接頭辞（<item id="1"> → "@id"）
+pub const XML_ATTRIBUTE_PREFIX: &str = "@";
+
+/// 属性や子要素を持つ要素のテキストを格納するキー
+pub const XML_TEXT_KEY: &str = "#text";
+
+/// 解析中の要素
+struct XmlElement {
+    name: String,
+    fields: Map<String, Value>,
+    text: String,
+}
+
+impl XmlElement {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            fields: Map::new(),
+            text: String::new(),
+        }
+    }
+
+    /// 要素を JSON の値に変換
+    ///
+    /// - 属性も子要素もない場合はテキストそのもの（空要素は ""）
+    /// - それ以外はオブジェクトで、テキストは "#text" に入れる
+    fn into_value(self) -> Value {
+        let text = self.text.trim();
+
+        if self.fields.is_empty() {
+            return text_to_value(text);
+        }
+
+        let mut fields = self.fields;
+        if !text.is_empty() {
+            fields.insert(XML_TEXT_KEY.to_string(), text_to_value(text));
+        }
+        Value::Object(fields)
+    }
+}
+
+/// XML を JSON に変換
+///
+/// 同じ名前の子要素が複数ある場合は配列になる。`array_elements` に含まれる要素は
+/// 1つしかなくても常に配列にする。
+pub fn parse_xml(content: &str, array_elements: &[String]) -> Result<Value, Error> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().expand_empty_elements = true;
+
+    // 先頭はドキュメント全体を表す名前なしの要素
+    let mut stack = vec![XmlElement::new(String::new())];
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| xml_error(&reader, e.to_string()))?;
+
+        match event {
+            Event::Start(start) => {
+                let element = start_element(&start).map_err(|e| xml_error(&reader, e))?;
+                stack.push(element);
+            }
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| xml_error(&reader, "unexpected closing tag".to_string()))?;
+                let parent = stack
+                    .last_mut()
+                    .ok_or_else(|| xml_error(&reader, "unexpected closing tag".to_string()))?;
+
+                let force_array = array_elements.contains(&element.name);
+                let name = element.name.clone();
+                insert_child(&mut parent.fields, name, element.into_value(), force_array);
+            }
+            Event::Text(text) => append_text(&mut stack, &text.xml10_content()),
+            Event::CData(cdata) => append_text(&mut stack, &cdata.xml10_content()),
+            Event::GeneralRef(reference) => {
+                // &amp; や &#169; などの参照をテキストに展開
+                let resolved = if reference.is_char_ref() {
+                    reference
+                        .resolve_char_ref()
+                        .map_err(|e| xml_error(&reader, e.to_string()))?
+                        .map(String::from)
+                } else {
+                    resolve_predefined_entity(&reference).map(String::from)
+                };
+                let text = resolved.unwrap_or_else(|| format!("&{};", &*reference));
+                append_text(&mut stack, &text);
+            }
+            Event::Eof => break,
+            // 宣言・コメント・処理命令・DOCTYPE は無視
+            _ => {}
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(Error::Xml(format!(
+            "unclosed element: <{}>",
+            stack.last().map_or("", |element| element.name.as_str())
+        )));
+    }
+
+    let document = stack.remove(0);
+    if document.fields.is_empty() {
+        return Err(Error::Xml("no root element found".to_string()));
+    }
+
+    Ok(Value::Object(document.fields))
+}
+
+fn start_element(start: &BytesStart) -> Result<XmlElement, String> {
+    let mut element = XmlElement::new(start.name().as_ref().to_string());
+
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| e.to_string())?;
+        let value = attribute
+            .normalized_value(XmlVersion::Implicit1_0)
+            .map_err(|e| e.to_string())?;
+
+        element.fields.insert(
+            format!("{}{}", XML_ATTRIBUTE_PREFIX, attribute.key.as_ref()),
+            text_to_value(&value),
+        );
+    }
+
+    Ok(element)
+}
+
+fn append_text(stack: &mut [XmlElement], text: &str) {
+    if let Some(element) = stack.last_mut() {
+        element.text.push_str(text);
+    }
+}
+
+/// 子要素を追加（同名の要素が既にあれば配列にまとめる）
+fn insert_child(fields: &mut Map<String, Value>, name: String, value: Value, force_array: bool) {
+    // 要素の値は文字列かオブジェクトなので、既存の配列は同名要素をまとめたもの
+    match fields.get_mut(&name) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None if force_array => {
+            fields.insert(name, Value::Array(vec![value]));
+        }
+        None => {
+            fields.insert(name, value);
+        }
+    }
+}
+
+/// テキストと属性値は文字列のまま保持（"1.10" や "007" の表記を失わないため）
+fn text_to_value(text: &str) -> Value {
+    Value::String(text.to_string())
+}
+
+fn xml_error<R>(reader: &Reader<R>, message: String) -> Error {
+    Error::Xml(format!(
+        "{} (at byte {})",
+        message,
+        reader.buffer_position()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_xml_mapping() {
+        let content = r#"<?xml version="1.0"?>
+<testsuite name="unit" tests="3">
+  <testcase name="a" time="0.1"/>
+  <testcase name="b" time="0.2">
+    <failure message="boom">expected &lt;1&gt; &amp; got 2</failure>
+  </testcase>
+  <testcase name="c"><skipped/></testcase>
+  <!-- comment -->
+  <system-out><![CDATA[log <output>]]></system-out>
+</testsuite>"#;
+
+        let value = parse_xml(content, &[]).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "testsuite": {
+                    "@name": "unit",
+                    "@tests": "3",
+                    "testcase": [
+                        {"@name": "a", "@time": "0.1"},
+                        {
+                            "@name": "b",
+                            "@time": "0.2",
+                            "failure": {"@message": "boom", "#text": "expected <1> & got 2"}
+                        },
+                        {"@name": "c", "skipped": ""}
+                    ],
+                    "system-out": "log <output>"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_xml_values_keep_formatting() {
+        let content =
+            r#"<package zip="01234"><version>1.10</version><enabled>true</enabled></package>"#;
+
+        let value = parse_xml(content, &[]).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "package": {"@zip": "01234", "version": "1.10", "enabled": "true"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_xml_forced_arrays() {
+        let content =
+            "<rss><channel><title>News</title><item><title>one</title></item></channel></rss>";
+
+        let value = parse_xml(content, &[]).unwrap();
+        assert_eq!(value["rss"]["channel"]["item"], json!({"title": "one"}));
+
+        let value = parse_xml(content, &["item".to_string()]).unwrap();
+        assert_eq!(value["rss"]["channel"]["item"], json!([{"title": "one"}]));
+        assert_eq!(value["rss"]["channel"]["title"], json!("News"));
+    }
+
+    #[test]
+    fn test_xml_errors() {
+        assert!(parse_xml("<a><b></a>", &[]).is_err());
+        assert!(parse_xml("<a>", &[]).is_err());
+        assert!(parse_xml("just text", &[]).is_err());
+    }
+}